@@ -0,0 +1,77 @@
+//! Letting the user know a reminder went off in ways other than the banner:
+//! the terminal bell, desktop notifications through escape sequences the
+//! terminal turns into one, and a command of the user's choosing.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use t_meter::reminder::{Delivery, Reminder};
+
+/// Ring, notify and run the command as `reminder` asks. Failures are
+/// logged rather than returned, so one broken delivery doesn't stop the
+/// others.
+pub fn deliver(reminder: &Reminder) {
+    let text = reminder.text();
+    let mut escapes = String::new();
+    for delivery in &reminder.delivery {
+        match delivery {
+            Delivery::Banner => {}
+            Delivery::Bell => escapes.push('\x07'),
+            Delivery::Osc9 => escapes.push_str(&format!("\x1b]9;{}\x07", clean(&text))),
+            Delivery::Osc777 => {
+                escapes.push_str(&format!("\x1b]777;notify;t-meter;{}\x07", clean(&text)))
+            }
+            Delivery::Command => run(reminder, &text),
+        }
+    }
+
+    if !escapes.is_empty() {
+        let mut stdout = io::stdout();
+        if let Err(err) = stdout.write_all(escapes.as_bytes()).and_then(|_| stdout.flush()) {
+            log::warn!("Couldn't ring or notify for '{}': {}", text, err);
+        }
+    }
+}
+
+/// `text` without control characters, which would end the escape sequence
+/// early, or semicolons, which separate its fields
+fn clean(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).map(|c| if c == ';' { ',' } else { c }).collect()
+}
+
+/// Start the reminder's command through the shell, without waiting for it
+fn run(reminder: &Reminder, text: &str) {
+    let Some(ref command) = reminder.command else {
+        log::warn!("Reminder '{}' is to run a command but doesn't say which", text);
+        return;
+    };
+
+    #[cfg(windows)]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    #[cfg(not(windows))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    // Output would draw over the screen
+    let child = shell
+        .arg(command)
+        .env("T_METER_REMINDER", text)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(err) => log::warn!("Couldn't run '{}' for a reminder: {}", command, err),
+    }
+}
@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use clap::{Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
+
+use t_meter::clock::{Clock, FixedClock, SimulatedClock, SystemClock};
+use t_meter::color::ColorSupport;
+use t_meter::{ProgressBarStyle, ThemeMode};
+
+use crate::report::ReportFormat;
+use crate::snapshot::SnapshotFormat;
+
+/// Fastest `--speed`, a day in under a second
+const MAX_SPEED: f64 = 100_000.0;
+
+/// A terminal-based day progress bar showing elapsed time and user markers
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Color palette to draw with, instead of detecting it from the terminal
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub colors: ColorChoice,
+
+    /// Show this moment instead of now, as HH:MM (today) or
+    /// "YYYY-MM-DD HH:MM". The interactive view keeps running from there.
+    #[arg(long, global = true, value_parser = parse_time)]
+    pub at: Option<DateTime<Local>>,
+
+    /// Run the interactive view's clock this many times faster than real
+    /// time, up to 100000
+    #[arg(long, default_value_t = 1.0, value_parser = parse_speed)]
+    pub speed: f64,
+
+    /// Least severe messages to write to the log file: off, error, warn,
+    /// info, debug or trace
+    #[arg(long, global = true, env = "T_METER_LOG", default_value = "info", value_parser = parse_log_level)]
+    pub log_level: LevelFilter,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Manage themes
+    Theme {
+        #[command(subcommand)]
+        action: ThemeCommand,
+    },
+    /// Render a single frame as SVG, HTML or ANSI text
+    Snapshot {
+        #[arg(long, value_enum, default_value_t = SnapshotFormat::Svg)]
+        format: SnapshotFormat,
+        /// Width in terminal cells
+        #[arg(long, default_value_t = 80)]
+        width: u16,
+        /// Height in terminal cells
+        #[arg(long, default_value_t = 24)]
+        height: u16,
+        /// Theme to draw with, instead of the configured one
+        #[arg(long)]
+        theme: Option<String>,
+        /// Theme mode to draw with: light, dark or auto
+        #[arg(long)]
+        mode: Option<ThemeMode>,
+        /// Progress bar style to draw with, instead of the configured one
+        #[arg(long, value_parser = parse_style)]
+        style: Option<ProgressBarStyle>,
+        /// File to write, instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the history of each day, with totals and averages
+    Report {
+        /// First day to include, as YYYY-MM-DD
+        #[arg(long, value_parser = parse_date)]
+        from: Option<NaiveDate>,
+        /// Last day to include, as YYYY-MM-DD
+        #[arg(long, value_parser = parse_date)]
+        to: Option<NaiveDate>,
+        #[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+        format: ReportFormat,
+        /// File to write, instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ThemeCommand {
+    /// Convert a base16/base24 YAML, Alacritty TOML or iTerm2 .itermcolors
+    /// color scheme into a t-meter theme
+    Import {
+        /// The color scheme file
+        file: PathBuf,
+        /// Theme name, instead of one made from the file name
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace an imported theme with the same name
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Detect from NO_COLOR, COLORTERM and TERM
+    Auto,
+    /// 24-bit color
+    Truecolor,
+    /// The xterm 256-color palette
+    #[value(name = "256")]
+    Ansi256,
+    /// The 16 ANSI colors
+    #[value(name = "16")]
+    Ansi16,
+    /// No color; the bar is told apart by its glyphs alone
+    None,
+}
+
+impl Cli {
+    /// Clock for the interactive view: the real one, unless `--at` or
+    /// `--speed` ask for a simulated one
+    pub fn clock(&self) -> Box<dyn Clock> {
+        match self.at {
+            None if self.speed == 1.0 => Box::new(SystemClock),
+            start => Box::new(SimulatedClock::new(start.unwrap_or_else(Local::now), self.speed)),
+        }
+    }
+
+    /// Whether the interactive view runs on the real time, so what happens
+    /// in it belongs in the history
+    pub fn real_time(&self) -> bool {
+        self.at.is_none() && self.speed == 1.0
+    }
+
+    /// Clock for a single frame: stopped at `--at`, or now
+    pub fn fixed_clock(&self) -> Box<dyn Clock> {
+        Box::new(FixedClock(self.at.unwrap_or_else(Local::now)))
+    }
+}
+
+impl ColorChoice {
+    pub fn resolve(&self) -> ColorSupport {
+        match self {
+            ColorChoice::Auto => ColorSupport::detect(),
+            ColorChoice::Truecolor => ColorSupport::TrueColor,
+            ColorChoice::Ansi256 => ColorSupport::Ansi256,
+            ColorChoice::Ansi16 => ColorSupport::Ansi16,
+            ColorChoice::None => ColorSupport::None,
+        }
+    }
+}
+
+/// A local time as `HH:MM[:SS]` today, or a date and time as
+/// `YYYY-MM-DD HH:MM[:SS]` (a `T` between them works too)
+fn parse_time(value: &str) -> Result<DateTime<Local>, String> {
+    let naive = ["%H:%M", "%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+        .map(|time| Local::now().date_naive().and_time(time))
+        .or_else(|| {
+            ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        })
+        .ok_or_else(|| format!("Invalid time '{}': use HH:MM or \"YYYY-MM-DD HH:MM\"", value))?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("'{}' doesn't exist in the local time zone", value))
+}
+
+/// A speed-up of the clock: above 0 and at most `MAX_SPEED`
+fn parse_speed(value: &str) -> Result<f64, String> {
+    let speed: f64 = value.parse().map_err(|_| format!("Invalid speed '{}': use a number", value))?;
+    if speed.is_finite() && speed > 0.0 && speed <= MAX_SPEED {
+        Ok(speed)
+    } else {
+        Err(format!("Invalid speed '{}': use a number above 0 and up to {}", value, MAX_SPEED))
+    }
+}
+
+/// A date as `YYYY-MM-DD`
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}': use YYYY-MM-DD", value))
+}
+
+/// A style by name, ignoring case, such as "smooth" or "Braille"
+fn parse_style(value: &str) -> Result<ProgressBarStyle, String> {
+    ProgressBarStyle::ALL
+        .into_iter()
+        .find(|style| format!("{:?}", style).eq_ignore_ascii_case(value))
+        .ok_or_else(|| {
+            let names: Vec<String> =
+                ProgressBarStyle::ALL.iter().map(|style| format!("{:?}", style).to_lowercase()).collect();
+            format!("Invalid style '{}': use one of {}", value, names.join(", "))
+        })
+}
+
+fn parse_log_level(value: &str) -> Result<LevelFilter, String> {
+    value.parse().map_err(|_| {
+        format!("Invalid log level '{}': use one of off, error, warn, info, debug, trace", value)
+    })
+}
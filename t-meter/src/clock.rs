@@ -0,0 +1,103 @@
+//! Where the app gets the current time from, so a frame can be drawn for any
+//! moment instead of only for now.
+
+use std::cell::Cell;
+use std::time::Instant;
+
+use chrono::{DateTime, Duration, Local, Utc};
+
+/// A source of the current time
+pub trait Clock {
+    /// The current local time
+    fn now(&self) -> DateTime<Local>;
+
+    /// How many seconds pass on this clock per real second. Zero for a
+    /// clock that only moves when told to.
+    fn rate(&self) -> f64 {
+        1.0
+    }
+}
+
+/// The real time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Always the same moment
+pub struct FixedClock(pub DateTime<Local>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        self.0
+    }
+
+    fn rate(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Stands in for the real time but only moves when it is set or moved on,
+/// for driving time by hand in tests
+#[derive(Debug)]
+pub struct ManualClock(Cell<DateTime<Local>>);
+
+impl ManualClock {
+    /// A clock showing `start`
+    pub fn new(start: DateTime<Local>) -> Self {
+        ManualClock(Cell::new(start))
+    }
+
+    /// Jump to `time`
+    pub fn set(&self, time: DateTime<Local>) {
+        self.0.set(time);
+    }
+
+    /// Move the clock on by `duration`
+    pub fn advance(&self, duration: Duration) {
+        self.0.set(self.0.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        self.0.get()
+    }
+}
+
+/// Starts at a given moment and runs from there, `speed` times faster than
+/// real time
+pub struct SimulatedClock {
+    start: DateTime<Local>,
+    started_at: Instant,
+    speed: f64,
+}
+
+impl SimulatedClock {
+    /// A clock showing `start` now and running on at `speed`
+    pub fn new(start: DateTime<Local>, speed: f64) -> Self {
+        SimulatedClock { start, started_at: Instant::now(), speed }
+    }
+}
+
+impl Clock for SimulatedClock {
+    /// Stops at the last moment chrono can represent rather than running
+    /// past it
+    fn now(&self) -> DateTime<Local> {
+        let elapsed = self.started_at.elapsed().as_secs_f64() * self.speed;
+        Duration::try_milliseconds((elapsed * 1000.0) as i64)
+            .and_then(|elapsed| self.start.checked_add_signed(elapsed))
+            .unwrap_or_else(|| {
+                let end =
+                    if elapsed < 0.0 { DateTime::<Utc>::MIN_UTC } else { DateTime::<Utc>::MAX_UTC };
+                end.with_timezone(&Local)
+            })
+    }
+
+    fn rate(&self) -> f64 {
+        self.speed
+    }
+}
@@ -0,0 +1,443 @@
+//! Color conversions, and fitting colors to what the terminal can show.
+
+use std::env;
+
+use ratatui::{buffer::Buffer, style::Color};
+
+/// The xterm default values of the 16 ANSI colors, by index
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of each channel in the 6×6×6 cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The best palette the terminal can show. `None` means no color at all,
+/// as asked for by `NO_COLOR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ColorSupport {
+    /// No color, only bold, reversed and the like
+    None,
+    /// The 16 ANSI colors
+    Ansi16,
+    /// The 256-color palette
+    Ansi256,
+    /// 24-bit RGB
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Work out color support from the environment: `NO_COLOR`, then
+    /// `COLORTERM`, then `TERM` and a few terminals known to do truecolor
+    /// without saying so.
+    pub fn detect() -> Self {
+        Self::from_vars(|name| env::var(name).unwrap_or_default())
+    }
+
+    /// `detect` with the variables looked up through `var`, so it can be
+    /// tried without touching the process environment.
+    fn from_vars(var: impl Fn(&str) -> String) -> Self {
+        if !var("NO_COLOR").is_empty() {
+            return ColorSupport::None;
+        }
+
+        let colorterm = var("COLORTERM").to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+
+        let term_program = var("TERM_PROGRAM");
+        if matches!(term_program.as_str(), "iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+            || !var("WT_SESSION").is_empty()
+        {
+            return ColorSupport::TrueColor;
+        }
+
+        let term = var("TERM").to_lowercase();
+        if term == "dumb" {
+            ColorSupport::None
+        } else if term.ends_with("-direct") {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Whether nothing can be told apart by color
+    pub fn is_monochrome(&self) -> bool {
+        *self == ColorSupport::None
+    }
+
+    /// The closest color this palette can show
+    pub fn adapt(&self, color: Color) -> Color {
+        match (self, color) {
+            (_, Color::Reset) | (ColorSupport::TrueColor, _) => color,
+            (ColorSupport::None, _) => Color::Reset,
+            (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+            (ColorSupport::Ansi256, _) => color,
+            (ColorSupport::Ansi16, Color::Rgb(..) | Color::Indexed(_)) => {
+                to_rgb(color).map_or(color, |rgb| ANSI_COLORS[nearest_16(rgb)])
+            }
+            (ColorSupport::Ansi16, _) => color,
+        }
+    }
+
+    /// Adapt every cell of a rendered frame, so anything drawn with colors
+    /// the terminal can't show degrades the same way
+    pub fn adapt_buffer(&self, buffer: &mut Buffer) {
+        if *self == ColorSupport::TrueColor {
+            return;
+        }
+
+        for cell in buffer.content.iter_mut() {
+            cell.fg = self.adapt(cell.fg);
+            cell.bg = self.adapt(cell.bg);
+        }
+    }
+}
+
+/// Ask the terminal for its background color with an OSC 11 query. Raw mode
+/// must already be on so the reply isn't echoed. Terminals that don't answer
+/// within `timeout` get `None`.
+///
+/// A DA1 query follows as a sentinel: every terminal answers it, and in
+/// order, so reading up to its reply leaves nothing of a late OSC 11 reply
+/// for the input thread to take as keystrokes.
+#[cfg(unix)]
+pub fn query_background(timeout: std::time::Duration) -> Option<(u8, u8, u8)> {
+    use std::io::Write;
+    use std::time::{Duration, Instant};
+
+    // How much longer to wait for the sentinel once `timeout` has passed
+    const DRAIN: Duration = Duration::from_millis(500);
+
+    let mut stdout = std::io::stdout();
+    stdout.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    stdout.flush().ok()?;
+
+    // Read straight from the descriptor: std's buffered stdin could swallow
+    // keypresses that arrive right after the reply.
+    let started = Instant::now();
+    let mut reply = Vec::new();
+    let sentinel = loop {
+        if let Some(start) = da1_reply_start(&reply) {
+            break start;
+        }
+
+        let remaining = (started + timeout + DRAIN).checked_duration_since(Instant::now())?;
+        let mut poll_fd = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+        // SAFETY: `poll_fd` is a valid pollfd for the duration of the call
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return None;
+        }
+
+        let mut byte = 0u8;
+        // SAFETY: reads at most one byte into `byte`
+        let read = unsafe { libc::read(libc::STDIN_FILENO, (&mut byte as *mut u8).cast(), 1) };
+        if read != 1 {
+            return None;
+        }
+        reply.push(byte);
+    };
+
+    if started.elapsed() > timeout {
+        return None;
+    }
+    parse_osc_color(&String::from_utf8_lossy(&reply[..sentinel]))
+}
+
+/// Where a DA1 reply (`ESC [ ? 6 2 ; 2 2 c`) that ends `bytes` starts
+#[cfg(unix)]
+fn da1_reply_start(bytes: &[u8]) -> Option<usize> {
+    let body = bytes.strip_suffix(b"c")?;
+    let start = body.windows(3).rposition(|w| w == b"\x1b[?")?;
+    body[start + 3..].iter().all(|b| b.is_ascii_digit() || *b == b';').then_some(start)
+}
+
+/// Ask the terminal for its background color. Only supported on Unix.
+#[cfg(not(unix))]
+pub fn query_background(_timeout: std::time::Duration) -> Option<(u8, u8, u8)> {
+    None
+}
+
+/// Parse the `rgb:RRRR/GGGG/BBBB` part of an OSC color reply. Channels may
+/// have one to four hex digits.
+fn parse_osc_color(reply: &str) -> Option<(u8, u8, u8)> {
+    let spec = reply.split("rgb:").nth(1)?;
+    let spec = spec.trim_end_matches(['\x07', '\x1b', '\\']);
+    let mut channels = spec.split('/').map(|channel| {
+        if !(1..=4).contains(&channel.len()) {
+            return None;
+        }
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = (1u32 << (4 * channel.len() as u32)) - 1;
+        Some((value * 255 / max) as u8)
+    });
+
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+/// A `#rrggbb`, `0xrrggbb` or bare `rrggbb` hex color
+pub fn parse_hex(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim();
+    let hex = hex
+        .strip_prefix('#')
+        .or_else(|| hex.strip_prefix("0x"))
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Relative luminance from 0 (black) to 1 (white)
+pub fn luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    0.2126 * srgb_to_linear(r) + 0.7152 * srgb_to_linear(g) + 0.0722 * srgb_to_linear(b)
+}
+
+/// The 16 ANSI colors, by index
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_16(rgb: (u8, u8, u8)) -> usize {
+    (0..16).min_by_key(|&i| distance(rgb, ANSI_RGB[i])).unwrap_or(0)
+}
+
+/// Nearest entry of the color cube or the grayscale ramp. The first 16
+/// entries are skipped since terminals often redefine them.
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len()).min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs()).unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (16 + 36 * r + 6 * g + b) as u8;
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+    if distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// RGB value of any color. Named and indexed colors use the xterm defaults,
+/// which is as close as we can get without asking the terminal. `Reset` has
+/// no value, since it means whatever the terminal's default is.
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        Color::Indexed(i) => return Some(indexed_rgb(i)),
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+    Some(ANSI_RGB[index])
+}
+
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_RGB[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Mix two colors in the Oklab color space, so the halfway point looks
+/// halfway to the eye. `Reset` can't be mixed and snaps to the nearer end.
+pub fn interpolate(start: Color, end: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let (Some(a), Some(b)) = (to_rgb(start), to_rgb(end)) else {
+        return if t < 0.5 { start } else { end };
+    };
+
+    let a = Oklab::from_rgb(a);
+    let b = Oklab::from_rgb(b);
+    let (r, g, b) = Oklab {
+        l: a.l + (b.l - a.l) * t,
+        a: a.a + (b.a - a.a) * t,
+        b: a.b + (b.b - a.b) * t,
+    }
+    .to_rgb();
+    Color::Rgb(r, g, b)
+}
+
+/// `color` with less blue and a little less green, like a night light.
+/// `amount` goes from 0, unchanged, to 1, as warm as it gets. `Reset` is
+/// left alone.
+pub fn warm(color: Color, amount: f64) -> Color {
+    let amount = amount.clamp(0.0, 1.0);
+    let Some((r, g, b)) = to_rgb(color).filter(|_| amount > 0.0) else {
+        return color;
+    };
+    let scale = |channel: u8, by: f64| (channel as f64 * (1.0 - by * amount)).round() as u8;
+    Color::Rgb(r, scale(g, 0.2), scale(b, 0.6))
+}
+
+/// A color in Björn Ottosson's Oklab space
+#[derive(Debug, Clone, Copy)]
+struct Oklab {
+    l: f64,
+    a: f64,
+    b: f64,
+}
+
+impl Oklab {
+    fn from_rgb((r, g, b): (u8, u8, u8)) -> Self {
+        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        Oklab {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+
+    fn to_rgb(self) -> (u8, u8, u8) {
+        let l = (self.l + 0.3963377774 * self.a + 0.2158037573 * self.b).powi(3);
+        let m = (self.l - 0.1055613458 * self.a - 0.0638541728 * self.b).powi(3);
+        let s = (self.l - 0.0894841775 * self.a - 1.2914855480 * self.b).powi(3);
+
+        (
+            linear_to_srgb(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            linear_to_srgb(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            linear_to_srgb(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+        )
+    }
+}
+
+fn srgb_to_linear(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(channel: f64) -> u8 {
+    let c = channel.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (c * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_osc_color_replies() {
+        assert_eq!(parse_osc_color("\x1b]11;rgb:ffff/0000/8080\x1b\\"), Some((255, 0, 128)));
+        assert_eq!(parse_osc_color("\x1b]11;rgb:f/0/8\x07"), Some((255, 0, 136)));
+        assert_eq!(parse_osc_color("rgb:ffffffff/0/0"), None);
+        assert_eq!(parse_osc_color("rgb:/ff/ff"), None);
+    }
+
+    fn detect_with(vars: &[(&str, &str)]) -> ColorSupport {
+        ColorSupport::from_vars(|name| {
+            vars.iter().find(|(key, _)| *key == name).map_or(String::new(), |(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn detects_color_support_from_the_environment() {
+        assert_eq!(detect_with(&[("COLORTERM", "truecolor")]), ColorSupport::TrueColor);
+        assert_eq!(detect_with(&[("COLORTERM", "24bit")]), ColorSupport::TrueColor);
+        assert_eq!(detect_with(&[("TERM", "xterm-direct")]), ColorSupport::TrueColor);
+        assert_eq!(detect_with(&[("TERM", "xterm-256color")]), ColorSupport::Ansi256);
+        assert_eq!(detect_with(&[("TERM", "xterm")]), ColorSupport::Ansi16);
+        assert_eq!(detect_with(&[]), ColorSupport::Ansi16);
+        assert_eq!(detect_with(&[("TERM", "dumb")]), ColorSupport::None);
+        assert_eq!(
+            detect_with(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]),
+            ColorSupport::None
+        );
+        assert_eq!(
+            detect_with(&[("NO_COLOR", ""), ("TERM", "xterm-256color")]),
+            ColorSupport::Ansi256
+        );
+    }
+
+    #[test]
+    fn picks_the_nearest_palette_color() {
+        assert_eq!(ColorSupport::Ansi256.adapt(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(ColorSupport::Ansi256.adapt(Color::Rgb(128, 128, 128)), Color::Indexed(244));
+        assert_eq!(ColorSupport::Ansi256.adapt(Color::Rgb(0, 95, 135)), Color::Indexed(24));
+        assert_eq!(ColorSupport::Ansi16.adapt(Color::Rgb(250, 10, 10)), Color::LightRed);
+        assert_eq!(ColorSupport::Ansi16.adapt(Color::Rgb(10, 10, 10)), Color::Black);
+        assert_eq!(ColorSupport::Ansi16.adapt(Color::Rgb(0, 200, 0)), Color::Green);
+        assert_eq!(ColorSupport::TrueColor.adapt(Color::Rgb(1, 2, 3)), Color::Rgb(1, 2, 3));
+    }
+}
@@ -1,33 +1,39 @@
-use crate::theme::{Theme, ThemeMode, get_theme_by_name, get_default_theme};
+use t_meter::reminder::Reminder;
+use t_meter::style::{CustomGlyphs, ProgressBarStyle};
+use t_meter::theme::{GradientMode, NightScheme, Theme, ThemeMode, get_default_theme};
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ProgressBarStyle {
-    Gradient,
-    Grainy,
-    Analog,
+/// Most minutes a reminder may go off before its target: a whole day
+const MAX_REMINDER_BEFORE: u32 = 24 * 60;
+
+/// How the screen is arranged. `Auto` picks one of the others from the size
+/// of the terminal on every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LayoutMode {
+    #[default]
+    Auto,
+    Full,
+    Compact,
+    Mini,
+    Vertical,
 }
 
-impl ProgressBarStyle {
+impl LayoutMode {
     pub fn cycle(&self) -> Self {
         match self {
-            ProgressBarStyle::Gradient => ProgressBarStyle::Grainy,
-            ProgressBarStyle::Grainy => ProgressBarStyle::Analog,
-            ProgressBarStyle::Analog => ProgressBarStyle::Gradient,
+            LayoutMode::Auto => LayoutMode::Full,
+            LayoutMode::Full => LayoutMode::Compact,
+            LayoutMode::Compact => LayoutMode::Mini,
+            LayoutMode::Mini => LayoutMode::Vertical,
+            LayoutMode::Vertical => LayoutMode::Auto,
         }
     }
 }
 
-impl Default for ProgressBarStyle {
-    fn default() -> Self {
-        ProgressBarStyle::Analog
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default = "default_theme_name")]
@@ -36,14 +42,67 @@ pub struct Config {
     #[serde(default = "default_theme_mode")]
     pub theme_mode: String,
 
+    /// With `theme_mode = "auto"`, pick the mode from the terminal's
+    /// background color instead of the time of day
+    #[serde(default)]
+    pub detect_background: bool,
+
+    /// Keep the terminal's own background instead of the theme's
+    #[serde(default)]
+    pub transparent_background: bool,
+
+    /// Location, used to find sunset for `theme_mode = "auto"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+
     #[serde(default)]
     pub progress_bar_style: ProgressBarStyle,
 
+    /// Overrides the theme's gradient mode when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gradient_mode: Option<GradientMode>,
+
+    #[serde(default)]
+    pub custom_glyphs: CustomGlyphs,
+
+    #[serde(default)]
+    pub layout: LayoutMode,
+
+    #[serde(default)]
+    pub show_ruler: bool,
+
     #[serde(default = "default_wake_up_time")]
     pub wake_up_time: String,
 
     #[serde(default = "default_bed_time")]
     pub bed_time: String,
+
+    /// Minutes before bed time to wind down in, with warmer colors, quotes
+    /// about rest and a countdown. 0 turns winding down off.
+    #[serde(default = "default_wind_down_minutes")]
+    pub wind_down_minutes: u32,
+
+    /// Ring the terminal bell when winding down starts
+    #[serde(default)]
+    pub wind_down_bell: bool,
+
+    /// Dim the screen from bed time until wake-up
+    #[serde(default = "default_night_screen")]
+    pub night_screen: bool,
+
+    /// Colors of the screen shown after bed time
+    #[serde(default)]
+    pub night: NightScheme,
+
+    /// Minutes the snooze key puts a reminder off for
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: u32,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<Reminder>,
 }
 
 fn default_theme_name() -> String {
@@ -62,14 +121,40 @@ fn default_bed_time() -> String {
     "23:00".to_string()
 }
 
+fn default_wind_down_minutes() -> u32 {
+    60
+}
+
+fn default_night_screen() -> bool {
+    true
+}
+
+fn default_snooze_minutes() -> u32 {
+    10
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             theme_name: default_theme_name(),
             theme_mode: default_theme_mode(),
+            detect_background: false,
+            transparent_background: false,
+            latitude: None,
+            longitude: None,
             progress_bar_style: ProgressBarStyle::default(),
+            gradient_mode: None,
+            custom_glyphs: CustomGlyphs::default(),
+            layout: LayoutMode::default(),
+            show_ruler: false,
             wake_up_time: default_wake_up_time(),
             bed_time: default_bed_time(),
+            wind_down_minutes: default_wind_down_minutes(),
+            wind_down_bell: false,
+            night_screen: default_night_screen(),
+            night: NightScheme::default(),
+            snooze_minutes: default_snooze_minutes(),
+            reminders: Vec::new(),
         }
     }
 }
@@ -85,7 +170,7 @@ impl Config {
         // If no config exists, generate the default one
         if !config_exists {
             if let Err(e) = Self::generate_default_config_file() {
-                eprintln!("Warning: Failed to generate default config: {}", e);
+                log::warn!("Failed to generate default config: {:#}", e);
             }
         }
         
@@ -93,12 +178,11 @@ impl Config {
             if path.exists() {
                 match Self::load_from_file(&path) {
                     Ok(config) => {
-                        eprintln!("Loaded config from: {}", path.display());
+                        log::info!("Loaded config from {}", path.display());
                         return config;
                     }
                     Err(e) => {
-                        eprintln!("Warning: Failed to load config from {}: {}", path.display(), e);
-                        eprintln!("Using default configuration.");
+                        log::warn!("Using the default configuration: {:#}", e);
                     }
                 }
             }
@@ -108,6 +192,39 @@ impl Config {
         Self::default()
     }
     
+    /// Read the config again while the app runs. Unlike `load` this neither
+    /// writes a default file nor prints where the config came from.
+    pub fn reload() -> Result<Self> {
+        match Self::get_config_paths().into_iter().find(|path| path.exists()) {
+            Some(path) => Self::load_from_file(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Every place a config can be loaded from, followed by the themes
+    /// directory
+    pub fn watched_paths() -> Vec<PathBuf> {
+        let mut paths = Self::get_config_paths();
+        paths.extend(Self::themes_dir());
+        paths
+    }
+
+    /// Directory for data the app keeps, such as the history
+    pub fn data_dir() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "t-meter").map(|dirs| dirs.data_dir().to_path_buf())
+    }
+
+    /// Directory for files the app writes for itself, such as the crash log
+    pub fn state_dir() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "t-meter")
+            .map(|dirs| dirs.state_dir().unwrap_or(dirs.data_local_dir()).to_path_buf())
+    }
+
+    /// Directory holding user themes, one TOML file per theme
+    pub fn themes_dir() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "t-meter").map(|dirs| dirs.config_dir().join("themes"))
+    }
+
     /// Get list of config file paths in priority order
     fn get_config_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
@@ -133,27 +250,48 @@ impl Config {
         let contents = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
         
-        let config: Config = toml::from_str(&contents)
+        let mut config: Config = toml::from_str(&contents)
             .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
         
+        for (key, fallback) in config.custom_glyphs.too_wide() {
+            log::warn!("custom_glyphs.{} isn't one cell wide; using '{}' instead", key, fallback);
+        }
+        config.reminders.retain(|reminder| {
+            let valid = reminder.before <= MAX_REMINDER_BEFORE;
+            if !valid {
+                log::warn!(
+                    "Reminder '{}' is more than {} minutes early, leaving it out",
+                    reminder.text(),
+                    MAX_REMINDER_BEFORE
+                );
+            }
+            valid
+        });
+        
         Ok(config)
     }
     
-    /// Get the theme based on config
-    pub fn get_theme(&self) -> Theme {
-        // Try to get the theme by name
-        if let Some(theme) = get_theme_by_name(&self.theme_name) {
-            theme
-        } else {
-            eprintln!("Warning: Theme '{}' not found, using default theme", self.theme_name);
-            get_default_theme()
+    /// Index of the configured theme in `themes`, or of the default theme
+    /// when there is none by that name
+    pub fn theme_index(&self, themes: &[Theme]) -> usize {
+        if let Some(index) = themes.iter().position(|t| t.name == self.theme_name) {
+            return index;
         }
+
+        let names: Vec<&str> = themes.iter().map(|t| t.name.as_str()).collect();
+        log::warn!(
+            "Theme '{}' not found (available: {}), using default theme",
+            self.theme_name,
+            names.join(", ")
+        );
+        let default = get_default_theme();
+        themes.iter().position(|t| t.name == default.name).unwrap_or(0)
     }
     
     /// Get the theme mode
     pub fn get_theme_mode(&self) -> ThemeMode {
         self.theme_mode.parse().unwrap_or_else(|_| {
-            eprintln!("Warning: Invalid theme mode '{}', using Light mode", self.theme_mode);
+            log::warn!("Invalid theme mode '{}', using Light mode", self.theme_mode);
             ThemeMode::Light
         })
     }
@@ -198,7 +336,7 @@ impl Config {
             }
             
             // Create comprehensive config template with TOML comments
-            let config_template = r#"# t-meter Configuration File
+            let config_template = r##"# t-meter Configuration File
 # Customize your t-meter experience by editing the values below
 
 # =============================================================================
@@ -206,11 +344,26 @@ impl Config {
 # =============================================================================
 # Available keyboard shortcuts while running t-meter:
 #   q or Ctrl+C  - Quit the application
+#   Ctrl+Z       - Suspend to the shell; fg brings it back
 #   t            - Cycle through available themes
-#   q or Ctrl+C  - Quit the application
-#   t            - Cycle through available themes
-#   d            - Toggle between light and dark mode
+#   g            - Browse all themes in a gallery
+#   e            - Edit the current theme
+#   d            - Cycle between light, dark and auto mode
 #   s            - Cycle through progress bar styles
+#   l            - Cycle through layouts (auto, full, compact, mini, vertical)
+#   r            - Toggle the hour ruler under the bar
+#   w            - Edit wake up time
+#   b            - Edit bed time
+#   f            - Start or stop a focus session
+#   u            - Mark that you got up just now
+#   z            - Mark that you're going to bed now
+#   p / x        - Snooze or dismiss the reminder showing
+#   Space        - Show the day instead of the night screen
+#   S            - Show sleep stats
+#   H            - Show past days on the bar's 24 hour axis
+#   h            - Show the help screen
+#   n            - Show recent warnings
+#   ?            - Open documentation
 
 # =============================================================================
 # THEME CONFIGURATION
@@ -222,14 +375,30 @@ impl Config {
 #   "forest"     - Natural green forest theme
 #   "sunset"     - Warm orange and red sunset theme
 #   "monochrome" - Pure black and white theme
+#   "contrast"   - High contrast theme
+#   "daylight"   - Sky colors from night through dawn, noon and dusk
 theme_name = "default"
 
 # Theme mode - Each theme has two modes:
 #   "light" - Light background optimized theme
 #   "dark"  - Dark background optimized theme
-#   "dark"  - Dark background optimized theme
+#   "auto"  - Dark from bed time (or sunset, if your location is set below)
+#             until wake-up, light the rest of the day
 theme_mode = "light"
 
+# With theme_mode = "auto", ask the terminal for its background color and
+# pick light or dark to match, instead of going by the time of day
+detect_background = false
+
+# Keep your terminal's background (and any transparency) instead of filling
+# the screen with the theme's background color
+transparent_background = false
+
+# Your location in degrees (north and east are positive), used to find
+# sunset for theme_mode = "auto"
+# latitude = 51.5
+# longitude = -0.1
+
 # =============================================================================
 # PROGRESS BAR CONFIGURATION
 # =============================================================================
@@ -238,8 +407,37 @@ theme_mode = "light"
 #   "Gradient" - Smooth gradient transition (Premium look)
 #   "Grainy"   - Retro segmented look
 #   "Analog"   - Vertical bars simulating an analog meter
+#   "Smooth"   - Gradient that fills in eighths of a cell, so it moves
+#                every couple of minutes instead of every 18
+#   "Dots"     - A row of dots
+#   "Braille"  - Braille cells that fill one dot column at a time
+#   "Hours"    - Gradient blocks with a gap between hours
+#   "Ascii"    - [####----], for terminals without box-drawing glyphs
+#   "Custom"   - Your own glyphs from [custom_glyphs] below
 progress_bar_style = "Gradient"
 
+# How gradients are laid over the bar. Leave unset to use the theme's choice.
+#   "Day"  - Anchored to the time of day, revealed as the day goes on
+#   "Fill" - Stretched over the filled part, so the whole gradient shows
+#   "Now"  - The whole filled part in the gradient's current color
+# gradient_mode = "Day"
+
+# =============================================================================
+# LAYOUT
+# =============================================================================
+
+# How the screen is arranged:
+#   "Auto"     - Pick a layout from the terminal size (recommended)
+#   "Full"     - Title, bar, markers, quote and legend
+#   "Compact"  - Bar with the current time and marker times
+#   "Mini"     - A single line, for tiny splits and status panes
+#   "Vertical" - The day drawn top-to-bottom like a calendar day view, with
+#                markers beside it. Suits tall narrow sidebar panes
+layout = "Auto"
+
+# Show an hour ruler under the bar (full and compact layouts)
+show_ruler = false
+
 # =============================================================================
 # SLEEP TRACKING
 # =============================================================================
@@ -250,23 +448,80 @@ wake_up_time = "07:00"
 # Time you go to bed (24-hour format HH:MM)
 bed_time = "23:00"
 
+# Minutes before bed time to wind down in: the colors turn warmer, the quotes
+# turn to rest and a countdown to bed time shows. 0 turns it off.
+wind_down_minutes = 60
+
+# Ring the terminal bell when winding down starts
+wind_down_bell = false
+
+# Dim the screen from bed time until wake-up. [Space] shows the day anyway.
+# Its colors are under [night] below.
+night_screen = true
+
+# =============================================================================
+# REMINDERS
+# =============================================================================
+
+# Minutes [p] puts off a reminder for; [x] dismisses it
+snooze_minutes = 10
+
+# Reminders go off while t-meter is running, at a marker ("wake", "noon" or
+# "bed") or any "HH:MM", optionally some minutes before. Each is delivered in
+# any of these ways:
+#   "banner"  - Across the top of the screen until snoozed or dismissed
+#   "bell"    - The terminal bell
+#   "osc9"    - A desktop notification, in iTerm2, Windows Terminal, kitty...
+#   "osc777"  - A desktop notification, in foot, rxvt-unicode, VTE terminals...
+#   "command" - Run `command` through the shell, with the reminder's text in
+#               $T_METER_REMINDER
+# Uncomment to be reminded half an hour before bed time:
+# [[reminders]]
+# at = "bed"
+# before = 30
+# message = "Time to wind down"
+# delivery = ["banner", "bell", "command"]
+# command = 'notify-send t-meter "$T_METER_REMINDER"'
+
+# =============================================================================
+# CUSTOM PROGRESS BAR GLYPHS
+# =============================================================================
+
+# Glyphs for the "Custom" progress bar style. Each is a single character.
+# vertical_indicator and vertical_marker optionally replace the indicator and
+# marker in the vertical layout, where they run across the bar.
+[custom_glyphs]
+filled = "="
+empty = " "
+indicator = ">"
+marker = "|"
+
+# =============================================================================
+# NIGHT SCREEN
+# =============================================================================
+
+# Colors of the screen shown after bed time, kept apart from the theme so the
+# night stays dim whatever the daytime colors are
+[night]
+background = "#08080c"
+foreground = "#464654"
+accent = "#6e6080"
+
 # =============================================================================
 # CUSTOMIZATION GUIDE
 # =============================================================================
 # 1. Edit the 'theme_name' value above to one of the available themes
-# 2. Edit the 'theme_mode' value to either 'light' or 'dark'
+# 2. Edit the 'theme_mode' value to 'light', 'dark' or 'auto'
 # 3. Save this file and restart t-meter to see your changes
 # 4. Press 't' while running to cycle through themes interactively
-# 4. Press 't' while running to cycle through themes interactively
-# 5. Press 'd' while running to toggle between light and dark modes
+# 5. Press 'd' while running to cycle between light, dark and auto modes
 # 6. Press 's' while running to cycle through progress bar styles
-"#;
+"##;
             
             fs::write(path, config_template)
                 .with_context(|| format!("Failed to write config file: {}", path.display()))?;
             
-            eprintln!("✓ Generated config file at: {}", path.display());
-            eprintln!("  You can customize your theme by editing this file.");
+            log::info!("Generated config file at {}", path.display());
             
             Ok(())
         } else {
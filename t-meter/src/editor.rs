@@ -0,0 +1,332 @@
+//! State of the in-app theme editor: a working copy of a theme, the color
+//! field being edited and the RGB/HSL picker used to adjust it.
+
+use ratatui::style::Color;
+
+use t_meter::color;
+use t_meter::theme::{ColorScheme, GradientMode, Theme, ThemeMode};
+
+/// Editable `ColorScheme` fields, in the order they are listed. The colors
+/// of the scheme's gradient stops follow them.
+pub const FIELDS: [&str; 17] = [
+    "background",
+    "foreground",
+    "title",
+    "progress_start",
+    "progress_end",
+    "progress_empty",
+    "progress_indicator",
+    "marker",
+    "marker_label",
+    "quote",
+    "legend_elapsed",
+    "legend_remaining",
+    "bar_background",
+    "quote_background",
+    "legend_background",
+    "highlight",
+    "error",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Picker {
+    Rgb,
+    Hsl,
+}
+
+impl Picker {
+    /// Channel names and their largest values
+    pub fn channels(&self) -> [(&'static str, f64); 3] {
+        match self {
+            Picker::Rgb => [("R", 255.0), ("G", 255.0), ("B", 255.0)],
+            Picker::Hsl => [("H", 359.0), ("S", 100.0), ("L", 100.0)],
+        }
+    }
+}
+
+/// Text being typed in the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditorInput {
+    Hex(String),
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ThemeEditor {
+    pub theme: Theme,
+    /// Which of the theme's schemes is being edited; never `Auto`
+    pub mode: ThemeMode,
+    pub field: usize,
+    pub picker: Picker,
+    pub channel: usize,
+    pub input: Option<EditorInput>,
+    /// Result of the last action, and whether it was an error
+    pub message: Option<(String, bool)>,
+    /// HSL of the current color while it is being adjusted, so hue and
+    /// saturation survive passing through grays
+    hsl: Option<[f64; 3]>,
+}
+
+impl ThemeEditor {
+    pub fn new(theme: &Theme, mode: ThemeMode) -> Self {
+        ThemeEditor {
+            theme: theme.clone(),
+            mode: if mode == ThemeMode::Dark { ThemeMode::Dark } else { ThemeMode::Light },
+            field: 0,
+            picker: Picker::Rgb,
+            channel: 0,
+            input: None,
+            message: None,
+            hsl: None,
+        }
+    }
+
+    pub fn colors(&self) -> &ColorScheme {
+        self.theme.get_colors(self.mode)
+    }
+
+    fn colors_mut(&mut self) -> &mut ColorScheme {
+        match self.mode {
+            ThemeMode::Dark => &mut self.theme.dark,
+            _ => &mut self.theme.light,
+        }
+    }
+
+    /// The selected field's color; `None` for an unset optional field
+    pub fn current(&self) -> Option<Color> {
+        field(self.colors(), self.field)
+    }
+
+    /// Values of the picker's channels for the selected field
+    pub fn channel_values(&self) -> [f64; 3] {
+        let rgb = self.current_rgb();
+        match self.picker {
+            Picker::Rgb => [rgb.0 as f64, rgb.1 as f64, rgb.2 as f64],
+            Picker::Hsl => self.hsl.unwrap_or_else(|| rgb_to_hsl(rgb)),
+        }
+    }
+
+    /// The selected field's color with one channel set to `value`
+    pub fn with_channel(&self, channel: usize, value: f64) -> Color {
+        let mut values = self.channel_values();
+        values[channel] = value;
+        let (r, g, b) = match self.picker {
+            Picker::Rgb => (values[0] as u8, values[1] as u8, values[2] as u8),
+            Picker::Hsl => hsl_to_rgb(values),
+        };
+        Color::Rgb(r, g, b)
+    }
+
+    fn current_rgb(&self) -> (u8, u8, u8) {
+        self.current().and_then(color::to_rgb).unwrap_or((0, 0, 0))
+    }
+
+    pub fn select_field(&mut self, forward: bool) {
+        let count = field_count(self.colors());
+        self.field = if forward { (self.field + 1) % count } else { (self.field + count - 1) % count };
+        self.hsl = None;
+    }
+
+    pub fn next_channel(&mut self) {
+        self.channel = (self.channel + 1) % 3;
+    }
+
+    pub fn toggle_picker(&mut self) {
+        self.picker = match self.picker {
+            Picker::Rgb => Picker::Hsl,
+            Picker::Hsl => Picker::Rgb,
+        };
+        self.hsl = None;
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            ThemeMode::Dark => ThemeMode::Light,
+            _ => ThemeMode::Dark,
+        };
+        // The other scheme may have fewer gradient stops
+        self.field = self.field.min(field_count(self.colors()) - 1);
+        self.hsl = None;
+    }
+
+    /// Move the selected channel by `delta`. Hue wraps around; everything
+    /// else stops at its ends.
+    pub fn adjust(&mut self, delta: f64) {
+        let (_, max) = self.picker.channels()[self.channel];
+        let mut values = self.channel_values();
+        values[self.channel] = if self.picker == Picker::Hsl && self.channel == 0 {
+            (values[0] + delta).rem_euclid(360.0)
+        } else {
+            (values[self.channel] + delta).clamp(0.0, max)
+        };
+
+        let (r, g, b) = match self.picker {
+            Picker::Rgb => (values[0] as u8, values[1] as u8, values[2] as u8),
+            Picker::Hsl => hsl_to_rgb(values),
+        };
+        if self.picker == Picker::Hsl {
+            self.hsl = Some(values);
+        }
+        let index = self.field;
+        set_field(self.colors_mut(), index, Some(Color::Rgb(r, g, b)));
+    }
+
+    /// Set the selected field from a typed hex value
+    pub fn set_hex(&mut self, hex: &str) -> Result<(), String> {
+        let (r, g, b) = color::parse_hex(hex).ok_or_else(|| format!("Invalid hex color: {}", hex))?;
+        let index = self.field;
+        set_field(self.colors_mut(), index, Some(Color::Rgb(r, g, b)));
+        self.hsl = None;
+        Ok(())
+    }
+
+    /// Unset the selected field, if it is optional
+    pub fn clear(&mut self) -> Result<(), String> {
+        if !is_optional(self.field) {
+            return Err(format!("{} can't be left unset", field_name(self.colors(), self.field)));
+        }
+        let index = self.field;
+        set_field(self.colors_mut(), index, None);
+        self.hsl = None;
+        Ok(())
+    }
+}
+
+/// Fields that may be left unset, falling back to the terminal or the
+/// frame background
+pub fn is_optional(index: usize) -> bool {
+    matches!(
+        FIELDS.get(index),
+        Some(&("background" | "bar_background" | "quote_background" | "legend_background"))
+    )
+}
+
+/// Number of fields, counting one per gradient stop
+pub fn field_count(colors: &ColorScheme) -> usize {
+    FIELDS.len() + colors.gradient.len()
+}
+
+/// Name a field is listed under. Gradient stops go by their time of day,
+/// or by how far along the bar they are.
+pub fn field_name(colors: &ColorScheme, index: usize) -> String {
+    let Some(stop) = index.checked_sub(FIELDS.len()).and_then(|i| colors.gradient.get(i)) else {
+        return FIELDS[index].to_string();
+    };
+    if colors.gradient_mode == GradientMode::Day {
+        let minutes = (stop.at * 24.0 * 60.0).round() as u32;
+        format!("gradient {:02}:{:02}", minutes / 60, minutes % 60)
+    } else {
+        format!("gradient {:.0}%", stop.at * 100.0)
+    }
+}
+
+/// Why editing a field may not show in the preview
+pub fn field_note(colors: &ColorScheme, index: usize) -> Option<&'static str> {
+    match FIELDS.get(index) {
+        Some(&"progress_start") if !colors.gradient.is_empty() => {
+            Some("unused: the gradient stops below shade the bar")
+        }
+        Some(&"progress_end") if !colors.gradient.is_empty() => {
+            Some("gradient styles use the stops below")
+        }
+        _ => None,
+    }
+}
+
+pub fn field(colors: &ColorScheme, index: usize) -> Option<Color> {
+    if let Some(stop) = index.checked_sub(FIELDS.len()) {
+        return colors.gradient.get(stop).map(|stop| stop.color);
+    }
+    match FIELDS[index] {
+        "background" => colors.background,
+        "foreground" => Some(colors.foreground),
+        "title" => Some(colors.title),
+        "progress_start" => Some(colors.progress_start),
+        "progress_end" => Some(colors.progress_end),
+        "progress_empty" => Some(colors.progress_empty),
+        "progress_indicator" => Some(colors.progress_indicator),
+        "marker" => Some(colors.marker),
+        "marker_label" => Some(colors.marker_label),
+        "quote" => Some(colors.quote),
+        "legend_elapsed" => Some(colors.legend_elapsed),
+        "legend_remaining" => Some(colors.legend_remaining),
+        "bar_background" => colors.bar_background,
+        "quote_background" => colors.quote_background,
+        "legend_background" => colors.legend_background,
+        "highlight" => Some(colors.highlight),
+        "error" => Some(colors.error),
+        _ => None,
+    }
+}
+
+/// Set a field. `None` only applies to optional fields.
+fn set_field(colors: &mut ColorScheme, index: usize, value: Option<Color>) {
+    if let Some(stop) = index.checked_sub(FIELDS.len()) {
+        if let (Some(stop), Some(value)) = (colors.gradient.get_mut(stop), value) {
+            stop.color = value;
+        }
+        return;
+    }
+    match (FIELDS[index], value) {
+        ("background", _) => colors.background = value,
+        ("bar_background", _) => colors.bar_background = value,
+        ("quote_background", _) => colors.quote_background = value,
+        ("legend_background", _) => colors.legend_background = value,
+        ("foreground", Some(value)) => colors.foreground = value,
+        ("title", Some(value)) => colors.title = value,
+        ("progress_start", Some(value)) => colors.progress_start = value,
+        ("progress_end", Some(value)) => colors.progress_end = value,
+        ("progress_empty", Some(value)) => colors.progress_empty = value,
+        ("progress_indicator", Some(value)) => colors.progress_indicator = value,
+        ("marker", Some(value)) => colors.marker = value,
+        ("marker_label", Some(value)) => colors.marker_label = value,
+        ("quote", Some(value)) => colors.quote = value,
+        ("legend_elapsed", Some(value)) => colors.legend_elapsed = value,
+        ("legend_remaining", Some(value)) => colors.legend_remaining = value,
+        ("highlight", Some(value)) => colors.highlight = value,
+        ("error", Some(value)) => colors.error = value,
+        _ => {}
+    }
+}
+
+/// Hue in degrees, saturation and lightness in percent
+fn rgb_to_hsl((r, g, b): (u8, u8, u8)) -> [f64; 3] {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return [0.0, 0.0, lightness * 100.0];
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    [hue.round(), (saturation * 100.0).round(), (lightness * 100.0).round()]
+}
+
+fn hsl_to_rgb([hue, saturation, lightness]: [f64; 3]) -> (u8, u8, u8) {
+    let (s, l) = (saturation / 100.0, lightness / 100.0);
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = l - chroma / 2.0;
+
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
@@ -0,0 +1,111 @@
+//! Everything that can wake the app up besides the clock: keys and resizes
+//! read on their own thread, changes to the config or the user's themes
+//! made while it runs, and signals.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+
+use crate::config::Config;
+
+pub enum AppEvent {
+    Key(KeyEvent),
+    Resize,
+    /// The config file or a theme file was written, created or removed
+    FilesChanged,
+    /// SIGTERM, SIGHUP or SIGINT
+    Quit,
+    /// SIGTSTP, such as from `kill -TSTP`. Ctrl+Z arrives as a key in raw mode.
+    #[cfg(unix)]
+    Suspend,
+    /// SIGCONT, after the app was stopped by something other than itself
+    #[cfg(unix)]
+    Resume,
+}
+
+/// Forward key presses and resizes from the terminal until it can't be read
+/// any more or nobody is listening
+pub fn read_input(sender: Sender<AppEvent>) {
+    thread::spawn(move || loop {
+        let event = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => AppEvent::Key(key),
+            Ok(Event::Resize(..)) => AppEvent::Resize,
+            Ok(_) => continue,
+            Err(_) => return,
+        };
+        if sender.send(event).is_err() {
+            return;
+        }
+    });
+}
+
+/// Forward the signals that ask the app to quit, stop or carry on
+#[cfg(unix)]
+pub fn forward_signals(sender: Sender<AppEvent>) -> std::io::Result<()> {
+    use signal_hook::consts::{SIGCONT, SIGHUP, SIGINT, SIGTERM, SIGTSTP};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGTERM, SIGHUP, SIGINT, SIGTSTP, SIGCONT])?;
+    thread::spawn(move || {
+        for signal in signals.forever() {
+            let event = match signal {
+                SIGTSTP => AppEvent::Suspend,
+                SIGCONT => AppEvent::Resume,
+                _ => AppEvent::Quit,
+            };
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Watch the config files and the themes directory. The directories are
+/// watched rather than the files, since editors often save by replacing the
+/// file. `None` when the platform has no watcher to offer; the app then
+/// only sees changes it makes itself.
+pub fn watch_files(sender: Sender<AppEvent>) -> Option<RecommendedWatcher> {
+    let watched = Config::watched_paths();
+    let themes_dir = Config::themes_dir();
+
+    let relevant = watched.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else { return };
+        let written = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        );
+        let changed = written
+            && event.paths.iter().any(|path| {
+                relevant.iter().any(|watched| same_file(path, watched))
+                    || themes_dir.as_deref().is_some_and(|dir| path.parent() == Some(dir))
+            });
+        if changed {
+            let _ = sender.send(AppEvent::FilesChanged);
+        }
+    })
+    .ok()?;
+
+    // The themes directory itself, and the directory each config file lives in
+    let dirs: BTreeSet<_> = watched
+        .iter()
+        .filter_map(|path| if path.is_dir() { Some(path.as_path()) } else { path.parent() })
+        .map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+        .filter(|dir| dir.is_dir())
+        .collect();
+    for dir in dirs {
+        let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+    }
+    Some(watcher)
+}
+
+/// Whether `changed`, as reported by the watcher, is the file at `watched`.
+/// Watchers report absolute paths, while `./t-meter.toml` is relative.
+fn same_file(changed: &Path, watched: &Path) -> bool {
+    changed == watched || std::path::absolute(watched).is_ok_and(|watched| changed == watched)
+}
@@ -0,0 +1,347 @@
+//! A day by day record of how t-meter was used: the wake-up and bed time in
+//! effect, when it was first opened and last seen, focus sessions, and when
+//! the user actually got up and went to bed.
+//!
+//! The history is stored as [JSON Lines]: a header line with the schema
+//! version, then one object per day, oldest first.
+//!
+//! ```text
+//! {"schema_version":1}
+//! {"date":"2026-10-17","wake":"07:00","bed":"23:00","first_opened":"2026-10-17T08:02:11+02:00","last_seen":"2026-10-17T22:41:05+02:00","focus":[{"start":"2026-10-17T09:00:00+02:00","end":"2026-10-17T09:50:00+02:00"}],"woke_up":"2026-10-17T07:12:40+02:00","went_to_bed":"2026-10-18T00:20:03+02:00"}
+//! ```
+//!
+//! A day runs from its wake-up time to the next one, so going to bed after
+//! midnight is recorded on the day before; see [`waking_day`].
+//!
+//! Fields a reader doesn't know are ignored, so new ones can be added
+//! without a new schema version. The version goes up when existing fields
+//! change meaning, and files with a newer version than this build knows are
+//! refused rather than misread.
+//!
+//! [JSON Lines]: https://jsonlines.org
+
+use std::io::{self, BufRead, Write};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::Schedule;
+
+/// The version of the format written by this build
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    schema_version: u32,
+}
+
+/// What happened on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Day {
+    /// The local date
+    pub date: NaiveDate,
+    /// Wake-up time in effect, in seconds since midnight
+    #[serde(with = "hm")]
+    pub wake: u32,
+    /// Bed time in effect, in seconds since midnight
+    #[serde(with = "hm")]
+    pub bed: u32,
+    /// When t-meter was first open on this day
+    pub first_opened: DateTime<Local>,
+    /// When t-meter was last seen open on this day
+    pub last_seen: DateTime<Local>,
+    /// Focus sessions started on this day, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus: Vec<FocusSession>,
+    /// When the user said they got up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub woke_up: Option<DateTime<Local>>,
+    /// When the user said they went to bed, which may be after midnight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub went_to_bed: Option<DateTime<Local>>,
+}
+
+impl Day {
+    /// The wake-up and bed time in effect
+    pub fn schedule(&self) -> Schedule {
+        Schedule::new(self.wake, self.bed)
+    }
+
+    /// Time spent in focus sessions that have ended
+    pub fn focus_time(&self) -> Duration {
+        self.focus.iter().filter_map(FocusSession::duration).sum()
+    }
+
+    /// Take in `theirs`, the same day as written by someone else. See
+    /// [`History::merge`].
+    fn merge(&mut self, base: Option<&Day>, theirs: &Day) {
+        self.first_opened = self.first_opened.min(theirs.first_opened);
+        self.last_seen = self.last_seen.max(theirs.last_seen);
+        if self.woke_up == base.and_then(|base| base.woke_up) {
+            self.woke_up = theirs.woke_up;
+        }
+        if self.went_to_bed == base.and_then(|base| base.went_to_bed) {
+            self.went_to_bed = theirs.went_to_bed;
+        }
+
+        for session in &theirs.focus {
+            let base = base
+                .and_then(|base| base.focus.iter().find(|base| base.start == session.start));
+            match self.focus.iter_mut().find(|ours| ours.start == session.start) {
+                Some(ours) if ours.end == base.and_then(|base| base.end) => ours.end = session.end,
+                Some(_) => {}
+                None => self.focus.push(*session),
+            }
+        }
+        self.focus.sort_by_key(|session| session.start);
+    }
+}
+
+/// A stretch of time set aside for focusing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct FocusSession {
+    /// When the session started
+    pub start: DateTime<Local>,
+    /// When it ended, or `None` while it's running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Local>>,
+}
+
+impl FocusSession {
+    /// How long the session lasted, or `None` while it's running
+    pub fn duration(&self) -> Option<Duration> {
+        self.end.map(|end| end - self.start)
+    }
+}
+
+/// Days read from `reader` one at a time, oldest first, without holding
+/// the whole history in memory. The header is checked up front; an empty
+/// file has no days.
+pub fn read_days<R: BufRead>(reader: R) -> io::Result<impl Iterator<Item = io::Result<Day>>> {
+    let mut lines =
+        reader.lines().filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()));
+
+    if let Some(header) = lines.next() {
+        let header: Header = serde_json::from_str(&header?).map_err(invalid_data)?;
+        if header.schema_version > SCHEMA_VERSION {
+            return Err(invalid_data(format!(
+                "history has schema version {}, newer than the {} this build reads",
+                header.schema_version, SCHEMA_VERSION
+            )));
+        }
+    }
+
+    Ok(lines.map(|line| serde_json::from_str(&line?).map_err(invalid_data)))
+}
+
+/// The day `now` belongs to: today from the planned wake-up time on, and
+/// the day before until then, so a late night counts towards the evening
+/// it started on.
+pub fn waking_day(now: DateTime<Local>, schedule: Schedule) -> NaiveDate {
+    let date = now.date_naive();
+    if now.num_seconds_from_midnight() < schedule.wake() {
+        date.pred_opt().unwrap_or(date)
+    } else {
+        date
+    }
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Every day recorded so far, oldest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    days: Vec<Day>,
+}
+
+impl History {
+    /// A history with no days in it
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a whole history, as written by [`History::write`]
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut days: Vec<Day> = read_days(reader)?.collect::<io::Result<_>>()?;
+        days.sort_by_key(|day| day.date);
+        Ok(History { days })
+    }
+
+    /// Write the header and every day
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let header = Header { schema_version: SCHEMA_VERSION };
+        writeln!(writer, "{}", serde_json::to_string(&header)?)?;
+        for day in &self.days {
+            writeln!(writer, "{}", serde_json::to_string(day)?)?;
+        }
+        Ok(())
+    }
+
+    /// Take in what someone else changed in `theirs` since both sides read
+    /// `base`, for t-meters open at once that write the same file. Days and
+    /// focus sessions from both sides are kept, and first opened and last
+    /// seen cover both. Marks and session ends that only they changed are
+    /// taken from them; when both sides changed one, ours is kept.
+    ///
+    /// ```
+    /// use chrono::{Local, TimeZone};
+    /// use t_meter::history::History;
+    /// use t_meter::Schedule;
+    ///
+    /// let schedule = Schedule::default();
+    /// let morning = Local.with_ymd_and_hms(2026, 10, 17, 7, 10, 0).unwrap();
+    /// let evening = Local.with_ymd_and_hms(2026, 10, 17, 23, 5, 0).unwrap();
+    ///
+    /// let base = History::new();
+    /// let mut ours = base.clone();
+    /// ours.mark_wake(morning, schedule);
+    /// let mut theirs = base.clone();
+    /// theirs.mark_bed(evening, schedule);
+    ///
+    /// ours.merge(&base, &theirs);
+    /// let day = ours.days()[0].clone();
+    /// assert_eq!((day.woke_up, day.went_to_bed), (Some(morning), Some(evening)));
+    /// ```
+    pub fn merge(&mut self, base: &History, theirs: &History) {
+        for day in &theirs.days {
+            match self.days.binary_search_by_key(&day.date, |ours| ours.date) {
+                Ok(index) => self.days[index].merge(base.day(day.date), day),
+                Err(index) => self.days.insert(index, day.clone()),
+            }
+        }
+    }
+
+    /// Every day, oldest first
+    pub fn days(&self) -> &[Day] {
+        &self.days
+    }
+
+    /// The entry for `date`, if t-meter was open that day
+    pub fn day(&self, date: NaiveDate) -> Option<&Day> {
+        self.days.iter().find(|day| day.date == date)
+    }
+
+    /// Note that t-meter is open at `now` with `schedule` in effect. Starts
+    /// the day's entry, or moves its last seen time and schedule on.
+    pub fn record(&mut self, now: DateTime<Local>, schedule: Schedule) -> &mut Day {
+        let now = now.with_nanosecond(0).unwrap_or(now);
+        let day = self.entry(now.date_naive(), now, schedule);
+        day.wake = schedule.wake();
+        day.bed = schedule.bed();
+        day.last_seen = day.last_seen.max(now);
+        day
+    }
+
+    /// The entry for `date`, started at `now` if there isn't one yet
+    fn entry(&mut self, date: NaiveDate, now: DateTime<Local>, schedule: Schedule) -> &mut Day {
+        let index = match self.days.iter().rposition(|day| day.date <= date) {
+            Some(index) if self.days[index].date == date => index,
+            found => {
+                let index = found.map_or(0, |index| index + 1);
+                let day = Day {
+                    date,
+                    wake: schedule.wake(),
+                    bed: schedule.bed(),
+                    first_opened: now,
+                    last_seen: now,
+                    focus: Vec::new(),
+                    woke_up: None,
+                    went_to_bed: None,
+                };
+                self.days.insert(index, day);
+                index
+            }
+        };
+        &mut self.days[index]
+    }
+
+    /// Note that the user got up at `now`. Marking it again moves it.
+    pub fn mark_wake(&mut self, now: DateTime<Local>, schedule: Schedule) -> &Day {
+        let day = self.record(now, schedule);
+        day.woke_up = Some(now.with_nanosecond(0).unwrap_or(now));
+        day
+    }
+
+    /// Note that the user went to bed at `now`, on the [`waking_day`] it
+    /// belongs to. Marking it again moves it.
+    pub fn mark_bed(&mut self, now: DateTime<Local>, schedule: Schedule) -> &Day {
+        self.record(now, schedule);
+        let now = now.with_nanosecond(0).unwrap_or(now);
+        let day = self.entry(waking_day(now, schedule), now, schedule);
+        day.went_to_bed = Some(now);
+        day
+    }
+
+    /// When the user got up and went to bed, as shown at `now`. Getting up
+    /// is looked up on the calendar date, where [`History::mark_wake`] puts
+    /// it even before the planned wake-up time, and going to bed on the
+    /// [`waking_day`], so it stays in view past midnight.
+    pub fn marks(
+        &self,
+        now: DateTime<Local>,
+        schedule: Schedule,
+    ) -> (Option<DateTime<Local>>, Option<DateTime<Local>>) {
+        let woke_up = self.day(now.date_naive()).and_then(|day| day.woke_up);
+        let went_to_bed = self.day(waking_day(now, schedule)).and_then(|day| day.went_to_bed);
+        (woke_up, went_to_bed)
+    }
+
+    /// The focus session that is running, if any
+    pub fn running_focus(&self) -> Option<&FocusSession> {
+        self.days.iter().rev().flat_map(|day| &day.focus).find(|session| session.end.is_none())
+    }
+
+    /// Start a focus session at `now`, recorded on the day it starts.
+    /// Nothing happens if one is already running.
+    pub fn start_focus(&mut self, now: DateTime<Local>, schedule: Schedule) {
+        if self.running_focus().is_some() {
+            return;
+        }
+        let start = now.with_nanosecond(0).unwrap_or(now);
+        self.record(now, schedule).focus.push(FocusSession { start, end: None });
+    }
+
+    /// End the running focus session at `now` and return it
+    pub fn end_focus(&mut self, now: DateTime<Local>) -> Option<FocusSession> {
+        let now = now.with_nanosecond(0).unwrap_or(now);
+        let session = self
+            .days
+            .iter_mut()
+            .rev()
+            .flat_map(|day| &mut day.focus)
+            .find(|session| session.end.is_none())?;
+        session.end = Some(now.max(session.start));
+        Some(*session)
+    }
+
+    /// End sessions left running by a t-meter that didn't get to end them,
+    /// at the last time it was seen that day
+    pub fn close_abandoned_focus(&mut self) {
+        for day in &mut self.days {
+            let last_seen = day.last_seen;
+            for session in day.focus.iter_mut().filter(|session| session.end.is_none()) {
+                session.end = Some(last_seen.max(session.start));
+            }
+        }
+    }
+}
+
+/// Times of day as `HH:MM`, like in the config
+mod hm {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::schedule::{format_hm, parse_hm};
+
+    pub fn serialize<S: Serializer>(seconds: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_hm(*seconds))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_hm(&text).map_err(de::Error::custom)
+    }
+}
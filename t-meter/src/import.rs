@@ -0,0 +1,274 @@
+//! Converting terminal color schemes into t-meter themes.
+//!
+//! Every supported format is read into a `Palette` of the background,
+//! foreground and 16 ANSI colors, which is then spread over the fields of a
+//! `ColorScheme` for both the light and the dark mode.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ratatui::style::Color;
+
+use t_meter::color;
+use t_meter::theme::{get_builtin_themes, ColorScheme, GradientMode, Theme};
+
+use crate::user_themes::{save_user_theme, user_theme_path};
+
+type Rgb = (u8, u8, u8);
+
+// Indices into `Palette::ansi`; the bright variant is 8 further on
+const RED: usize = 1;
+const GREEN: usize = 2;
+const YELLOW: usize = 3;
+const BLUE: usize = 4;
+const CYAN: usize = 6;
+
+/// The colors shared by terminal color scheme formats
+struct Palette {
+    background: Rgb,
+    foreground: Rgb,
+    /// Black, red, green, yellow, blue, magenta, cyan and white, then their
+    /// bright variants
+    ansi: [Rgb; 16],
+}
+
+/// Convert the color scheme in `path` into a theme and save it to the
+/// themes directory. The format is picked from the file extension. Returns
+/// the theme and where it was saved.
+pub fn import_theme(path: &Path, name: Option<&str>, force: bool) -> Result<(Theme, PathBuf)> {
+    let contents =
+        fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+
+    let palette = match extension.as_str() {
+        "yaml" | "yml" => parse_base16(&String::from_utf8_lossy(&contents)),
+        "toml" => parse_alacritty(&String::from_utf8_lossy(&contents)),
+        "itermcolors" | "plist" => parse_iterm(&contents),
+        _ => bail!(
+            "Unsupported file type '{}': expected .yaml/.yml (base16 or base24), \
+             .toml (Alacritty) or .itermcolors (iTerm2)",
+            path.display()
+        ),
+    }
+    .with_context(|| format!("Failed to import {}", path.display()))?;
+
+    let name = match name {
+        Some(name) => slug(name),
+        None => theme_name(path),
+    };
+    if name.is_empty() {
+        bail!("Can't make a theme name without letters or digits; pick one with --name");
+    }
+    if get_builtin_themes().iter().any(|theme| theme.name == name) {
+        bail!("'{}' is a built-in theme; pick another name with --name", name);
+    }
+
+    let theme = Theme::new(name.clone(), palette.scheme(false), palette.scheme(true));
+
+    let existing = user_theme_path(&name)?;
+    if existing.exists() && !force {
+        bail!("{} already exists; use --force to replace it", existing.display());
+    }
+
+    let theme_path = save_user_theme(&theme)?;
+    Ok((theme, theme_path))
+}
+
+/// Theme name from the file stem, so "Tomorrow Night.itermcolors" becomes
+/// "tomorrow-night"
+fn theme_name(path: &Path) -> String {
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("imported");
+    slug(stem)
+}
+
+/// Lowercase `text` with anything but letters and digits turned into dashes,
+/// for theme names that double as file names
+pub fn slug(text: &str) -> String {
+    let name: String = text
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    name.trim_matches('-').to_string()
+}
+
+impl Palette {
+    /// Light or dark colors from the palette. The dark mode sits on the
+    /// darkest of the background, foreground and blacks, and the light mode
+    /// on the lightest of them and the whites, so a dark scheme still gets a
+    /// usable light mode and the other way round. Of each accent's normal and
+    /// bright variants the one that stands out more is used, and one that is
+    /// still too close to the background is pulled towards the foreground.
+    fn scheme(&self, dark: bool) -> ColorScheme {
+        let luminance = color::luminance;
+        let by_luminance = |a: &Rgb, b: &Rgb| luminance(*a).total_cmp(&luminance(*b));
+        let (darker, lighter) = if luminance(self.background) <= luminance(self.foreground) {
+            (self.background, self.foreground)
+        } else {
+            (self.foreground, self.background)
+        };
+
+        let (background, foreground) = if dark {
+            let darkest = [darker, self.ansi[0], self.ansi[8]].into_iter().min_by(by_luminance);
+            (darkest.unwrap_or(darker), lighter)
+        } else {
+            let lightest = [lighter, self.ansi[7], self.ansi[15]].into_iter().max_by(by_luminance);
+            (lightest.unwrap_or(lighter), darker)
+        };
+
+        let accent = |index: usize| {
+            let (normal, bright) = (self.ansi[index], self.ansi[index + 8]);
+            let brighter = luminance(bright) >= luminance(normal);
+            let rgb = if brighter == dark { bright } else { normal };
+            let (a, b) = (luminance(rgb), luminance(background));
+            // WCAG contrast ratio; 3:1 is the minimum for large text
+            if (a.max(b) + 0.05) / (a.min(b) + 0.05) < 3.0 {
+                color::interpolate(rgb_color(rgb), rgb_color(foreground), 0.5)
+            } else {
+                rgb_color(rgb)
+            }
+        };
+        // Shades between the background and the foreground
+        let shade = |t: f64| color::interpolate(rgb_color(background), rgb_color(foreground), t);
+
+        let mut scheme = ColorScheme::default();
+        scheme.background = Some(rgb_color(background));
+        scheme.foreground = rgb_color(foreground);
+        scheme.title = accent(BLUE);
+        scheme.progress_start = accent(BLUE);
+        scheme.progress_end = accent(CYAN);
+        scheme.progress_empty = shade(0.15);
+        scheme.progress_indicator = accent(YELLOW);
+        scheme.marker = shade(0.75);
+        scheme.marker_label = shade(0.55);
+        scheme.quote = shade(0.6);
+        scheme.legend_elapsed = accent(GREEN);
+        scheme.legend_remaining = shade(0.35);
+        scheme.bar_background = None;
+        scheme.quote_background = None;
+        scheme.legend_background = None;
+        scheme.highlight = accent(YELLOW);
+        scheme.error = accent(RED);
+        scheme.gradient = Vec::new();
+        scheme.gradient_mode = GradientMode::Day;
+        scheme
+    }
+}
+
+fn rgb_color((r, g, b): Rgb) -> Color {
+    Color::Rgb(r, g, b)
+}
+
+/// base16 and base24 schemes, either with the `baseXX` keys at the top level
+/// or under `palette`. ANSI slots follow the base16-shell mapping; base24's
+/// `base12`..`base17` provide the bright accents.
+fn parse_base16(contents: &str) -> Result<Palette> {
+    let document: serde_norway::Value =
+        serde_norway::from_str(contents).context("Invalid YAML")?;
+    let slots = document.get("palette").unwrap_or(&document);
+
+    let slot = |key: &str| -> Option<Result<Rgb>> {
+        let value = slots.get(key)?;
+        Some(
+            value
+                .as_str()
+                .and_then(color::parse_hex)
+                .ok_or_else(|| anyhow!("Invalid color for {}: {:?}", key, value)),
+        )
+    };
+    let required = |key: &str| slot(key).unwrap_or_else(|| Err(anyhow!("Missing {}", key)));
+    // base24 brights, falling back to the base16 accent
+    let bright = |key: &str, fallback: &str| slot(key).unwrap_or_else(|| required(fallback));
+
+    Ok(Palette {
+        background: required("base00")?,
+        foreground: required("base05")?,
+        ansi: [
+            required("base00")?,
+            required("base08")?,
+            required("base0B")?,
+            required("base0A")?,
+            required("base0D")?,
+            required("base0E")?,
+            required("base0C")?,
+            required("base05")?,
+            required("base03")?,
+            bright("base12", "base08")?,
+            bright("base14", "base0B")?,
+            bright("base13", "base0A")?,
+            bright("base16", "base0D")?,
+            bright("base17", "base0E")?,
+            bright("base15", "base0C")?,
+            required("base07")?,
+        ],
+    })
+}
+
+/// The `[colors]` tables of an Alacritty config. Missing bright colors fall
+/// back to the normal ones.
+fn parse_alacritty(contents: &str) -> Result<Palette> {
+    const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+    let document: toml::Table = toml::from_str(contents).context("Invalid TOML")?;
+    let colors = document.get("colors").context("Missing [colors]")?;
+
+    let lookup = |table: &str, key: &str| -> Option<Result<Rgb>> {
+        let value = colors.get(table)?.get(key)?;
+        Some(
+            value
+                .as_str()
+                .and_then(color::parse_hex)
+                .ok_or_else(|| anyhow!("Invalid color for colors.{}.{}: {}", table, key, value)),
+        )
+    };
+    let required = |table: &str, key: &str| {
+        lookup(table, key).unwrap_or_else(|| Err(anyhow!("Missing colors.{}.{}", table, key)))
+    };
+
+    let mut ansi = [(0, 0, 0); 16];
+    for (i, name) in NAMES.iter().enumerate() {
+        ansi[i] = required("normal", name)?;
+        ansi[i + 8] = lookup("bright", name).unwrap_or(Ok(ansi[i]))?;
+    }
+
+    Ok(Palette {
+        background: required("primary", "background")?,
+        foreground: required("primary", "foreground")?,
+        ansi,
+    })
+}
+
+/// An iTerm2 `.itermcolors` property list. Components are read as sRGB
+/// whatever color space they were saved in, which is close enough for a
+/// terminal.
+fn parse_iterm(contents: &[u8]) -> Result<Palette> {
+    let document = plist::Value::from_reader(std::io::Cursor::new(contents))
+        .context("Invalid property list")?;
+    let colors = document.as_dictionary().context("Expected a dictionary of colors")?;
+
+    let entry = |key: &str| -> Result<Rgb> {
+        let color = colors
+            .get(key)
+            .and_then(|value| value.as_dictionary())
+            .with_context(|| format!("Missing {}", key))?;
+        let component = |name: &str| -> Result<u8> {
+            let value = color
+                .get(name)
+                .and_then(|value| value.as_real())
+                .with_context(|| format!("Missing {} of {}", name, key))?;
+            Ok((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+        Ok((component("Red Component")?, component("Green Component")?, component("Blue Component")?))
+    };
+
+    let mut ansi = [(0, 0, 0); 16];
+    for (i, slot) in ansi.iter_mut().enumerate() {
+        *slot = entry(&format!("Ansi {} Color", i))?;
+    }
+
+    Ok(Palette {
+        background: entry("Background Color")?,
+        foreground: entry("Foreground Color")?,
+        ansi,
+    })
+}
@@ -0,0 +1,43 @@
+//! The parts of [t-meter] that are useful outside of it: the time of day
+//! math, themes, the quotes and a [`DayBar`] widget for drawing the day in
+//! any [ratatui] app.
+//!
+//! ```
+//! use ratatui::{backend::TestBackend, Terminal};
+//! use t_meter::{theme, DayBar, DayBarState, Schedule};
+//! use t_meter::clock::SystemClock;
+//!
+//! let theme = theme::get_default_theme();
+//! let schedule = Schedule::parse("07:00", "23:00").unwrap();
+//! let mut state = DayBarState::new(SystemClock);
+//!
+//! let mut terminal = Terminal::new(TestBackend::new(60, 2)).unwrap();
+//! terminal
+//!     .draw(|frame| {
+//!         let bar = DayBar::new(&theme.light).markers(schedule.markers());
+//!         frame.render_stateful_widget(bar, frame.area(), &mut state);
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! [t-meter]: https://github.com/jordan-jakisa/t-meter
+
+#![warn(missing_docs)]
+
+pub mod clock;
+pub mod color;
+pub mod history;
+pub mod quotes;
+pub mod reminder;
+pub mod schedule;
+pub mod scheduler;
+pub mod style;
+pub mod stats;
+pub mod sun;
+pub mod theme;
+pub mod widgets;
+
+pub use schedule::Schedule;
+pub use style::ProgressBarStyle;
+pub use theme::{ColorScheme, Theme, ThemeMode};
+pub use widgets::{DayBar, DayBarState};
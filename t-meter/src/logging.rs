@@ -0,0 +1,153 @@
+//! Where warnings and diagnostics go: a log file in the state directory,
+//! rotated as it grows, and a list of recent warnings for the notifications
+//! panel. Until the interactive view takes over the screen, warnings are
+//! also printed to stderr.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Local};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::config::Config;
+
+// The log is rotated once it grows past this size, keeping this many old
+// files next to it as t-meter.log.1, t-meter.log.2, ...
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+const KEEP_OLD_FILES: usize = 2;
+
+// Warnings kept for the notifications panel
+const RECENT_WARNINGS: usize = 50;
+
+/// A warning or error, as listed in the notifications panel
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub message: String,
+}
+
+struct Logger {
+    file: Mutex<Option<File>>,
+    recent: Mutex<VecDeque<Notification>>,
+    warnings: AtomicUsize,
+    echo: AtomicBool,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Log at `level` and above from here on. Without a writable state
+/// directory only the notifications panel and stderr see anything.
+pub fn init(level: LevelFilter) {
+    let logger = LOGGER.get_or_init(|| Logger {
+        file: Mutex::new(log_path().and_then(|path| open(&path))),
+        recent: Mutex::new(VecDeque::new()),
+        warnings: AtomicUsize::new(0),
+        echo: AtomicBool::new(true),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Stop printing warnings to stderr, once the interactive view owns the
+/// screen
+pub fn stop_echo() {
+    if let Some(logger) = LOGGER.get() {
+        logger.echo.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Recent warnings and errors, oldest first
+pub fn recent() -> Vec<Notification> {
+    LOGGER
+        .get()
+        .map(|logger| logger.recent.lock().unwrap().iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// How many warnings and errors have been logged since the start, for
+/// telling which ones haven't been seen yet
+pub fn warning_count() -> usize {
+    LOGGER.get().map_or(0, |logger| logger.warnings.load(Ordering::Relaxed))
+}
+
+/// `t-meter.log` in the state directory
+pub fn log_path() -> Option<PathBuf> {
+    Config::state_dir().map(|dir| dir.join("t-meter.log"))
+}
+
+fn open(path: &Path) -> Option<File> {
+    fs::create_dir_all(path.parent()?).ok()?;
+    OpenOptions::new().create(true).append(true).open(path).ok()
+}
+
+/// Move `t-meter.log` to `t-meter.log.1`, and so on, dropping the oldest
+fn rotate(path: &Path) -> Option<File> {
+    let old = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+    for n in (1..KEEP_OLD_FILES).rev() {
+        let _ = fs::rename(old(n), old(n + 1));
+    }
+    let _ = fs::rename(path, old(1));
+    open(path)
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let time = Local::now();
+        let message = record.args().to_string();
+
+        if let Some(path) = log_path() {
+            let mut file = self.file.lock().unwrap();
+            let full = file
+                .as_ref()
+                .and_then(|file| file.metadata().ok())
+                .is_some_and(|m| m.len() >= MAX_FILE_SIZE);
+            if full {
+                *file = rotate(&path);
+            }
+            if let Some(file) = file.as_mut() {
+                let _ = writeln!(
+                    file,
+                    "{} {:<5} {}: {}",
+                    time.format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+                    record.level(),
+                    record.target(),
+                    message
+                );
+            }
+        }
+
+        if record.level() > Level::Warn {
+            return;
+        }
+        if self.echo.load(Ordering::Relaxed) {
+            let label = if record.level() == Level::Error { "Error" } else { "Warning" };
+            eprintln!("{}: {}", label, message);
+        }
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() == RECENT_WARNINGS {
+            recent.pop_front();
+        }
+        recent.push_back(Notification { time, level: record.level(), message });
+        self.warnings.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
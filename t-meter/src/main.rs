@@ -1,32 +1,71 @@
-use anyhow::Result;
-use chrono::{Local, Timelike};
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    prelude::*,
-    widgets::Paragraph,
-};
-use std::io;
-
-mod quotes;
-mod theme;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+use clap::Parser;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter};
+use std::iter;
+use std::sync::mpsc::{self, RecvTimeoutError};
+
+mod alerts;
+mod cli;
+mod events;
+mod import;
+mod logging;
+mod report;
+mod snapshot;
 mod config;
-
-use theme::{Theme, ThemeMode, ColorScheme};
-use config::{Config, ProgressBarStyle};
+mod editor;
+mod terminal;
+mod ui;
+mod usage;
+mod user_themes;
+
+use cli::{Cli, ColorChoice, Command, ThemeCommand};
+use editor::{EditorInput, ThemeEditor};
+use events::AppEvent;
+use terminal::TerminalGuard;
+use usage::Recorder;
+use t_meter::clock::Clock;
+use t_meter::color::{self, ColorSupport};
+use t_meter::history;
+use t_meter::reminder::{Delivery, Reminder, Reminders, Target};
+use t_meter::schedule::{self, Schedule};
+use t_meter::scheduler::Scheduler;
+use t_meter::theme::{self, Theme, ThemeMode, ColorScheme};
+use t_meter::{sun, ProgressBarStyle};
+use config::{Config, LayoutMode};
 
 struct AppState {
     current_theme_index: usize,
     theme_mode: ThemeMode,
+    /// Mode picked from the terminal's background color, for `ThemeMode::Auto`
+    background_mode: Option<ThemeMode>,
     progress_bar_style: ProgressBarStyle,
+    layout_mode: LayoutMode,
+    show_ruler: bool,
+    color_support: ColorSupport,
     themes: Vec<Theme>,
     config: Config,
     input_mode: InputMode,
     input_buffer: String,
     error_message: Option<String>,
+    /// Theme and mode highlighted in the gallery, applied on Enter
+    gallery_index: usize,
+    gallery_mode: ThemeMode,
+    /// Working copy of the theme open in the editor
+    editor: Option<ThemeEditor>,
+    /// Warnings logged up to when the notifications panel was last open
+    seen_warnings: usize,
+    /// Days the history screen is scrolled back from today
+    history_offset: u32,
+    reminders: Reminders,
+    /// Reminders showing as banners, the newest last
+    banners: Vec<Reminder>,
+    /// Day whose night the night screen was put away for
+    night_hidden_on: Option<NaiveDate>,
+    recorder: Recorder,
+    clock: Box<dyn Clock>,
 }
 
 #[derive(PartialEq)]
@@ -35,15 +74,83 @@ enum InputMode {
     EditingWakeUp,
     EditingBedTime,
     Help,
+    Gallery,
+    ThemeEditor,
+    Notifications,
+    Stats,
+    History,
 }
 
 impl AppState {
+    fn new(config: Config, color_support: ColorSupport, clock: Box<dyn Clock>) -> Self {
+        let themes = user_themes::get_all_themes();
+        let current_theme_index = config.theme_index(&themes);
+
+        AppState {
+            current_theme_index,
+            theme_mode: config.get_theme_mode(),
+            background_mode: None,
+            progress_bar_style: config.progress_bar_style,
+            layout_mode: config.layout,
+            show_ruler: config.show_ruler,
+            color_support,
+            themes,
+            config,
+            input_mode: InputMode::Normal,
+            input_buffer: String::new(),
+            error_message: None,
+            gallery_index: 0,
+            gallery_mode: ThemeMode::Light,
+            editor: None,
+            seen_warnings: 0,
+            history_offset: 0,
+            reminders: Reminders::new(clock.now()),
+            banners: Vec::new(),
+            night_hidden_on: None,
+            recorder: Recorder::in_memory(),
+            clock,
+        }
+    }
+
+    fn now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
+
     fn get_current_theme(&self) -> &Theme {
         &self.themes[self.current_theme_index]
     }
     
+    /// Colors to draw with. The theme editor previews its working copy.
     fn get_colors(&self) -> &ColorScheme {
-        self.get_current_theme().get_colors(self.theme_mode)
+        match self.editor {
+            Some(ref editor) if self.input_mode == InputMode::ThemeEditor => editor.colors(),
+            _ => self.get_current_theme().get_colors(self.effective_mode()),
+        }
+    }
+
+    /// The configured mode, with `Auto` resolved to light or dark
+    fn effective_mode(&self) -> ThemeMode {
+        if self.theme_mode != ThemeMode::Auto {
+            return self.theme_mode;
+        }
+
+        if let Some(mode) = self.background_mode {
+            return mode;
+        }
+
+        let now = self.now();
+        let sunset = match (self.config.latitude, self.config.longitude) {
+            (Some(latitude), Some(longitude)) => sun::sunset(
+                now.date_naive(),
+                latitude,
+                longitude,
+                now.offset().local_minus_utc(),
+            ),
+            _ => None,
+        };
+
+        let schedule = self.schedule();
+        ThemeMode::for_time(now.num_seconds_from_midnight(), schedule.wake(), schedule.bed(), sunset)
     }
     
     fn cycle_theme(&mut self) {
@@ -52,12 +159,179 @@ impl AppState {
         let _ = self.config.save();
     }
     
+    /// Show the gallery with the current theme and mode highlighted
+    fn open_gallery(&mut self) {
+        self.gallery_index = self.current_theme_index;
+        self.gallery_mode = self.effective_mode();
+        self.input_mode = InputMode::Gallery;
+    }
+
+    fn gallery_step(&mut self, forward: bool) {
+        let count = self.themes.len();
+        self.gallery_index = if forward {
+            (self.gallery_index + 1) % count
+        } else {
+            (self.gallery_index + count - 1) % count
+        };
+    }
+
+    /// Switch to the theme and mode picked in the gallery and save them. In
+    /// `Auto` mode only the theme changes, so the mode keeps following the
+    /// terminal or the time of day.
+    fn apply_gallery(&mut self) {
+        self.current_theme_index = self.gallery_index;
+        if self.theme_mode != ThemeMode::Auto {
+            self.theme_mode = self.gallery_mode;
+            self.config.theme_mode = self.theme_mode.as_str().to_string();
+        }
+        self.config.theme_name = self.themes[self.current_theme_index].name.clone();
+        let _ = self.config.save();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Note in the history that the app is open
+    fn record_usage(&mut self) {
+        let (now, schedule) = (self.now(), self.schedule());
+        self.recorder.record(now, schedule);
+    }
+
+    /// Deliver the reminders whose time came since the last look. Returns
+    /// whether any did.
+    fn check_reminders(&mut self) -> bool {
+        let (now, schedule) = (self.now(), self.schedule());
+        let due = self.reminders.due(&self.reminder_rules(), schedule, now);
+        for reminder in &due {
+            log::info!("Reminder: {}", reminder.text());
+            alerts::deliver(reminder);
+            if reminder.delivery.contains(&Delivery::Banner) {
+                self.banners.push(reminder.clone());
+            }
+        }
+        !due.is_empty()
+    }
+
+    /// When the next reminder goes off
+    fn next_reminder(&self) -> Option<DateTime<Local>> {
+        self.reminders.next(&self.reminder_rules(), self.schedule(), self.now())
+    }
+
+    /// The configured reminders, and the bell when winding down starts
+    fn reminder_rules(&self) -> Vec<Reminder> {
+        let mut rules = self.config.reminders.clone();
+        if self.config.wind_down_bell && self.config.wind_down_minutes > 0 {
+            let wind_down = Reminder::new(Target::Bed)
+                .before(self.config.wind_down_minutes)
+                .message("Time to wind down")
+                .delivery([Delivery::Bell]);
+            rules.push(wind_down);
+        }
+        rules
+    }
+
+    /// Seconds left until bed time while winding down before it
+    fn wind_down_left(&self) -> Option<u32> {
+        let window = self.config.wind_down_minutes * 60;
+        let left = self.schedule().until_bed(self.now().num_seconds_from_midnight());
+        (left > 0 && left <= window).then_some(left)
+    }
+
+    /// How warm to make the colors, from 0 when winding down starts to 1 at
+    /// bed time. The gallery and the editor keep the theme's own colors.
+    fn warmth(&self) -> Option<f64> {
+        if matches!(self.input_mode, InputMode::Gallery | InputMode::ThemeEditor) {
+            return None;
+        }
+        let window = self.config.wind_down_minutes * 60;
+        self.wind_down_left().map(|left| 1.0 - left as f64 / window as f64)
+    }
+
+    /// Whether the night screen is up instead of the bar: from bed time
+    /// until wake-up, unless put away for tonight
+    fn showing_night(&self) -> bool {
+        let (now, schedule) = (self.now(), self.schedule());
+        self.config.night_screen
+            && self.input_mode == InputMode::Normal
+            && schedule.is_night(now.num_seconds_from_midnight())
+            && self.night_hidden_on != Some(history::waking_day(now, schedule))
+    }
+
+    /// Show the day instead of the night screen for the rest of the night
+    fn hide_night(&mut self) {
+        if self.showing_night() {
+            self.night_hidden_on = Some(history::waking_day(self.now(), self.schedule()));
+        }
+    }
+
+    /// Put off the newest banner for `snooze_minutes`
+    fn snooze_reminder(&mut self) {
+        if let Some(reminder) = self.banners.pop() {
+            let until = self.now() + Duration::minutes(self.config.snooze_minutes.into());
+            self.reminders.snooze(reminder, until);
+        }
+    }
+
+    fn dismiss_reminder(&mut self) {
+        self.banners.pop();
+    }
+
+    fn toggle_focus(&mut self) {
+        let (now, schedule) = (self.now(), self.schedule());
+        self.recorder.toggle_focus(now, schedule);
+    }
+
+    /// Note that the user got up, which also puts the night screen away
+    fn mark_wake(&mut self) {
+        self.hide_night();
+        let (now, schedule) = (self.now(), self.schedule());
+        self.recorder.mark_wake(now, schedule);
+    }
+
+    fn mark_bed(&mut self) {
+        let (now, schedule) = (self.now(), self.schedule());
+        self.recorder.mark_bed(now, schedule);
+    }
+
+    fn open_notifications(&mut self) {
+        self.seen_warnings = logging::warning_count();
+        self.input_mode = InputMode::Notifications;
+    }
+
+    /// Warnings logged since the notifications panel was last open
+    fn unseen_warnings(&self) -> usize {
+        logging::warning_count() - self.seen_warnings
+    }
+
+    fn open_editor(&mut self) {
+        self.editor = Some(ThemeEditor::new(self.get_current_theme(), self.effective_mode()));
+        self.input_mode = InputMode::ThemeEditor;
+    }
+
+    /// Save the editor's theme as a user theme called `name` and switch to it
+    fn save_editor(&mut self, name: &str) -> Result<()> {
+        let name = import::slug(name);
+        if name.is_empty() {
+            anyhow::bail!("Enter a name for the theme");
+        }
+        if theme::get_builtin_themes().iter().any(|theme| theme.name == name) {
+            anyhow::bail!("'{}' is a built-in theme; pick another name", name);
+        }
+        let Some(ref mut editor) = self.editor else {
+            return Ok(());
+        };
+
+        editor.theme.name = name.clone();
+        user_themes::save_user_theme(&editor.theme)?;
+
+        self.themes = user_themes::get_all_themes();
+        self.current_theme_index = self.themes.iter().position(|t| t.name == name).unwrap_or(0);
+        self.config.theme_name = name;
+        let _ = self.config.save();
+        Ok(())
+    }
+
     fn toggle_mode(&mut self) {
         self.theme_mode = self.theme_mode.toggle();
-        self.config.theme_mode = match self.theme_mode {
-            ThemeMode::Light => "light".to_string(),
-            ThemeMode::Dark => "dark".to_string(),
-        };
+        self.config.theme_mode = self.theme_mode.as_str().to_string();
         let _ = self.config.save();
     }
 
@@ -67,79 +341,154 @@ impl AppState {
         let _ = self.config.save();
     }
 
-    fn get_wake_up_seconds(&self) -> u32 {
-        parse_time(&self.config.wake_up_time)
+    fn cycle_layout(&mut self) {
+        self.layout_mode = self.layout_mode.cycle();
+        self.config.layout = self.layout_mode;
+        let _ = self.config.save();
     }
 
-    fn get_bed_seconds(&self) -> u32 {
-        parse_time(&self.config.bed_time)
+    fn toggle_ruler(&mut self) {
+        self.show_ruler = !self.show_ruler;
+        self.config.show_ruler = self.show_ruler;
+        let _ = self.config.save();
     }
-}
 
-fn parse_time(time_str: &str) -> u32 {
-    let parts: Vec<&str> = time_str.split(':').collect();
-    if parts.len() == 2 {
-        let h: u32 = parts[0].parse().unwrap_or(0);
-        let m: u32 = parts[1].parse().unwrap_or(0);
-        h * 3600 + m * 60
-    } else {
-        0
+    /// Wake-up and bed time from the config. A time that doesn't parse
+    /// counts as midnight.
+    fn schedule(&self) -> Schedule {
+        Schedule::new(
+            schedule::parse_hm(&self.config.wake_up_time).unwrap_or(0),
+            schedule::parse_hm(&self.config.bed_time).unwrap_or(0),
+        )
     }
-}
 
-fn validate_time(time_str: &str) -> Result<u32, String> {
-    let parts: Vec<&str> = time_str.split(':').collect();
-    if parts.len() != 2 {
-        return Err("Invalid format. Use HH:MM".to_string());
-    }
-    
-    let h: u32 = parts[0].parse().map_err(|_| "Invalid hour".to_string())?;
-    let m: u32 = parts[1].parse().map_err(|_| "Invalid minute".to_string())?;
-    
-    if h >= 24 {
-        return Err("Hour must be 0-23".to_string());
+    /// Pick up changes to the config file and the user's themes made
+    /// outside the app. A config that no longer parses is left alone until
+    /// it's fixed.
+    fn reload(&mut self) {
+        let config = match Config::reload() {
+            Ok(config) => config,
+            Err(err) => {
+                log::warn!("Keeping the previous configuration: {:#}", err);
+                return;
+            }
+        };
+
+        self.themes = user_themes::get_all_themes();
+        self.current_theme_index = config.theme_index(&self.themes);
+        self.gallery_index = self.gallery_index.min(self.themes.len() - 1);
+        self.theme_mode = config.get_theme_mode();
+        self.progress_bar_style = config.progress_bar_style;
+        self.layout_mode = config.layout;
+        self.show_ruler = config.show_ruler;
+        self.config = config;
     }
-    if m >= 60 {
-        return Err("Minute must be 0-59".to_string());
+}
+
+/// Run a subcommand instead of the progress bar
+fn run_command(command: Command, cli: &Cli) -> Result<()> {
+    match command {
+        Command::Snapshot { format, width, height, theme, mode, style, output } => {
+            let mut app_state =
+                AppState::new(Config::load(), ColorSupport::TrueColor, cli.fixed_clock());
+            // Files aren't tied to this terminal, so only an explicit choice degrades them
+            if cli.colors != ColorChoice::Auto {
+                app_state.color_support = cli.colors.resolve();
+            }
+            if let Some(name) = theme {
+                app_state.current_theme_index = app_state
+                    .themes
+                    .iter()
+                    .position(|t| t.name == name)
+                    .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", name))?;
+            }
+            if let Some(mode) = mode {
+                app_state.theme_mode = mode;
+            }
+            if let Some(style) = style {
+                app_state.progress_bar_style = style;
+            }
+
+            let buffer = snapshot::render(&app_state, width, height)?;
+            let contents = snapshot::export(&buffer, format);
+            match output {
+                Some(path) => std::fs::write(&path, contents)
+                    .with_context(|| format!("Failed to write snapshot: {}", path.display()))?,
+                None => print!("{}", contents),
+            }
+        }
+        Command::Report { from, to, format, output } => {
+            let range = report::Range { from, to };
+            if let (Some(from), Some(to)) = (from, to) {
+                anyhow::ensure!(from <= to, "--from {} is after --to {}", from, to);
+            }
+            let path = usage::history_path().context("No data directory found for the history")?;
+            let history: Box<dyn BufRead> = match File::open(&path) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                // Nothing recorded yet: just the headings
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Box::new(io::empty()),
+                Err(err) => {
+                    return Err(err).with_context(|| format!("Failed to read {}", path.display()))
+                }
+            };
+            let written = match output {
+                Some(ref path) => {
+                    let file = File::create(path)
+                        .with_context(|| format!("Failed to write report: {}", path.display()))?;
+                    report::write(history, BufWriter::new(file), format, range)
+                }
+                None => report::write(history, io::stdout().lock(), format, range),
+            };
+            written.with_context(|| format!("Failed to report on {}", path.display()))?;
+        }
+        Command::Theme { action: ThemeCommand::Import { file, name, force } } => {
+            let (theme, path) = import::import_theme(&file, name.as_deref(), force)?;
+            println!("Imported theme '{}' to {}", theme.name, path.display());
+            println!("Set theme_name = \"{}\" in your config or press [t] to pick it.", theme.name);
+        }
     }
-    
-    Ok(h * 3600 + m * 60)
+    Ok(())
 }
 
 fn main() -> Result<()> {
+    let mut cli = Cli::parse();
+    logging::init(cli.log_level);
+
+    if let Some(command) = cli.command.take() {
+        return run_command(command, &cli);
+    }
+
     // Load configuration
     let config = Config::load();
-    let all_themes = theme::get_all_themes();
-    let current_theme_index = all_themes
-        .iter()
-        .position(|t| t.name == config.theme_name)
-        .unwrap_or(0);
-    
-    let mut app_state = AppState {
-        current_theme_index,
-        theme_mode: config.get_theme_mode(),
-        progress_bar_style: config.progress_bar_style,
-        themes: all_themes,
-        config,
-        input_mode: InputMode::Normal,
-        input_buffer: String::new(),
-        error_message: None,
-    };
+    let mut app_state = AppState::new(config, cli.colors.resolve(), cli.clock());
+    if cli.real_time() {
+        app_state.recorder = Recorder::open();
+    }
     
     // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    terminal::install_panic_hook();
+    let mut terminal = TerminalGuard::new()?;
+    logging::stop_echo();
+
+    if app_state.theme_mode == ThemeMode::Auto && app_state.config.detect_background {
+        app_state.background_mode =
+            color::query_background(std::time::Duration::from_millis(200)).map(|rgb| {
+                if color::luminance(rgb) < 0.2 {
+                    ThemeMode::Dark
+                } else {
+                    ThemeMode::Light
+                }
+            });
+    }
 
     // Run app
     let res = run_app(&mut terminal, &mut app_state);
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    drop(terminal);
+
+    let (now, schedule) = (app_state.now(), app_state.schedule());
+    app_state.recorder.finish(now, schedule);
 
     if let Err(err) = res {
         println!("{:?}", err);
@@ -148,398 +497,274 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app_state: &mut AppState) -> Result<()> {
-    loop {
-        terminal.draw(|f| ui(f, app_state))?;
-
-        if event::poll(std::time::Duration::from_millis(250))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match app_state.input_mode {
-                        InputMode::Normal => match key.code {
-                            KeyCode::Char('q') => return Ok(()),
-                            KeyCode::Char('c') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => return Ok(()),
-                            KeyCode::Char('t') => app_state.cycle_theme(),
-                            KeyCode::Char('d') => app_state.toggle_mode(),
-                            KeyCode::Char('s') => app_state.cycle_style(),
-                            KeyCode::Char('h') => {
-                                app_state.input_mode = InputMode::Help;
-                            },
-                            KeyCode::Char('w') => {
-                                app_state.input_mode = InputMode::EditingWakeUp;
-                                app_state.input_buffer = app_state.config.wake_up_time.clone();
-                                app_state.error_message = None;
-                            },
-                            KeyCode::Char('b') => {
-                                app_state.input_mode = InputMode::EditingBedTime;
-                                app_state.input_buffer = app_state.config.bed_time.clone();
-                                app_state.error_message = None;
-                            },
-                            KeyCode::Char('?') => {
-                                let _ = open::that("https://github.com/jordan-jakisa/t-meter/blob/main/docs.md");
-                            },
-                            _ => {}
-                        },
-                        InputMode::Help => match key.code {
-                            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('q') => {
-                                app_state.input_mode = InputMode::Normal;
-                            },
-                            _ => {}
-                        },
-                        InputMode::EditingWakeUp | InputMode::EditingBedTime => match key.code {
-                            KeyCode::Enter => {
-                                match validate_time(&app_state.input_buffer) {
-                                    Ok(_) => {
-                                        match app_state.input_mode {
-                                            InputMode::EditingWakeUp => app_state.config.wake_up_time = app_state.input_buffer.clone(),
-                                            InputMode::EditingBedTime => app_state.config.bed_time = app_state.input_buffer.clone(),
-                                            _ => {}
-                                        }
-                                        let _ = app_state.config.save();
-                                        app_state.input_mode = InputMode::Normal;
-                                        app_state.error_message = None;
-                                    },
-                                    Err(err) => {
-                                        app_state.error_message = Some(err);
-                                    }
-                                }
-                                app_state.input_buffer.clear();
-                            },
-                            KeyCode::Esc => {
-                                app_state.input_mode = InputMode::Normal;
-                                app_state.input_buffer.clear();
-                                app_state.error_message = None;
-                            },
-                            KeyCode::Backspace => {
-                                app_state.input_buffer.pop();
-                            },
-                            KeyCode::Char(c) => {
-                                if c.is_digit(10) || c == ':' {
-                                    app_state.input_buffer.push(c);
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
-fn ui(frame: &mut Frame, app_state: &AppState) {
-    let colors = app_state.get_colors();
-    
-    // Help Screen
-    if app_state.input_mode == InputMode::Help {
-        let area = frame.area();
-        let help_text = vec![
-            " ",
-            "┌───────────────────── HELP ─────────────────────┐",
-            "│                                                │",
-            "│  [h]     Toggle this help screen               │",
-            "│  [q]     Quit application                      │",
-            "│                                                │",
-            "│  [t]     Cycle themes                          │",
-            "│  [d]     Toggle dark/light mode                │",
-            "│  [s]     Cycle progress bar style              │",
-            "│                                                │",
-            "│  [w]     Edit wake up time                     │",
-            "│  [b]     Edit bed time                         │",
-            "│  [?]     Open documentation                    │",
-            "│                                                │",
-            "│  Press [h], [q], or [Esc] to close             │",
-            "│                                                │",
-            "└────────────────────────────────────────────────┘",
-        ];
-        
-        let help_paragraph = Paragraph::new(help_text.join("\n"))
-            .style(Style::default().fg(colors.foreground))
-            .alignment(Alignment::Center);
-        
-        frame.render_widget(help_paragraph, area);
+fn handle_editor_key(app_state: &mut AppState, code: KeyCode) {
+    let Some(ref mut editor) = app_state.editor else {
+        app_state.input_mode = InputMode::Normal;
         return;
-    }
-    
-    let now = Local::now();
-    let seconds_since_midnight = now.num_seconds_from_midnight();
-    let total_seconds = 24 * 60 * 60;
-    let ratio = seconds_since_midnight as f64 / total_seconds as f64;
-    
-    let area = frame.area();
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1), // Top padding
-            Constraint::Length(1), // Title
-            Constraint::Length(2), // Spacer
-            Constraint::Length(2), // Floating Time
-            Constraint::Length(4), // Bar
-            Constraint::Length(1), // Ticks
-            Constraint::Length(1), // Time Labels
-            Constraint::Length(1), // Marker Labels
-            Constraint::Length(2), // Spacer
-            Constraint::Length(4), // Zen Quote
-            Constraint::Min(1),    // Legend
-        ])
-        .split(area);
-
-    // Title
-    let title = Paragraph::new("TIME IS FLEETING")
-        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
-        .alignment(Alignment::Center);
-    frame.render_widget(title, layout[1]);
-
-    // Dimensions
-    let width = layout[4].width as usize;
-    if width < 2 { return; }
-    
-    // Floating Time
-    let time_str = now.format("%H:%M").to_string();
-    let time_pos = (ratio * width as f64).round() as usize;
-    let time_pos = time_pos.min(width - 1);
-    
-    // Calculate safe position for time string to avoid clipping
-    let time_len = time_str.len();
-    let time_start = if time_pos >= time_len / 2 {
-        time_pos - time_len / 2
-    } else {
-        0
     };
-    let time_start = time_start.min(width.saturating_sub(time_len));
-    
-    let mut time_line = String::from(" ".repeat(width));
-    if time_start < width {
-        let end = (time_start + time_len).min(width);
-        time_line.replace_range(time_start..end, &time_str);
-    }
-    
-    let mut pointer_line = String::from(" ".repeat(width));
-    if time_pos < width {
-        pointer_line.replace_range(time_pos..time_pos+1, "▼");
-    }
 
-    let floating_time = Paragraph::new(format!("{}\n{}", time_line, pointer_line))
-        .style(Style::default().fg(colors.foreground).add_modifier(Modifier::BOLD));
-    frame.render_widget(floating_time, layout[3]);
-
-    // Progress Bar
-    let filled_width = (ratio * width as f64).round() as usize;
-    
-    let mut spans = Vec::with_capacity(width);
-    
-    // Helper to interpolate colors
-    fn interpolate_color(start: Color, end: Color, t: f64) -> Color {
-        if let (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) = (start, end) {
-            let r = (r1 as f64 + (r2 as f64 - r1 as f64) * t) as u8;
-            let g = (g1 as f64 + (g2 as f64 - g1 as f64) * t) as u8;
-            let b = (b1 as f64 + (b2 as f64 - b1 as f64) * t) as u8;
-            Color::Rgb(r, g, b)
-        } else {
-            start // Fallback if not RGB
-        }
-    }
-
-    for i in 0..width {
-        
-        // Determine base style
-        let (char_str, style) = match app_state.progress_bar_style {
-            ProgressBarStyle::Gradient => {
-                if i < filled_width {
-                    let t = i as f64 / width as f64;
-                    let color = interpolate_color(colors.progress_start, colors.progress_end, t);
-                    ("█", Style::default().fg(color))
-                } else {
-                    ("█", Style::default().fg(colors.progress_empty))
-                }
+    // Typing a hex value or a name for saving
+    if let Some(ref mut input) = editor.input {
+        let buffer = match input {
+            EditorInput::Hex(buffer) | EditorInput::Name(buffer) => buffer,
+        };
+        match code {
+            KeyCode::Char(c) => buffer.push(c),
+            KeyCode::Backspace => {
+                buffer.pop();
             },
-            ProgressBarStyle::Grainy => {
-                if i < filled_width {
-                    ("▓", Style::default().fg(colors.progress_end))
-                } else {
-                    ("░", Style::default().fg(colors.progress_empty))
-                }
+            KeyCode::Esc => editor.input = None,
+            KeyCode::Enter => match editor.input.take() {
+                Some(EditorInput::Hex(hex)) => {
+                    editor.message = editor.set_hex(&hex).err().map(|err| (err, true));
+                },
+                Some(EditorInput::Name(name)) => {
+                    let result = app_state.save_editor(&name);
+                    let Some(ref mut editor) = app_state.editor else { return };
+                    editor.message = Some(match result {
+                        Ok(()) => (format!("Saved theme '{}'", editor.theme.name), false),
+                        Err(err) => (err.to_string(), true),
+                    });
+                },
+                None => {}
             },
-            ProgressBarStyle::Analog => {
-                if i < filled_width {
-                    ("║", Style::default().fg(colors.progress_end))
-                } else {
-                    ("│", Style::default().fg(colors.progress_empty))
-                }
-            }
-        };
-
-        // Calculate positions for wake and bed time
-        let wake_pos = (app_state.get_wake_up_seconds() as f64 / total_seconds as f64 * width as f64).round() as usize;
-        let bed_pos = (app_state.get_bed_seconds() as f64 / total_seconds as f64 * width as f64).round() as usize;
-
-        if i == time_pos {
-            spans.push(Span::styled("┃", Style::default().fg(colors.progress_indicator).add_modifier(Modifier::BOLD)));
-        } else if i == wake_pos || i == bed_pos {
-            spans.push(Span::styled("│", Style::default().fg(colors.marker).add_modifier(Modifier::BOLD)));
-        } else {
-            spans.push(Span::styled(char_str, style));
+            _ => {}
         }
+        return;
     }
-    
-    let line = Line::from(spans);
-    let bar_paragraph = Paragraph::new(vec![line.clone(), line.clone(), line.clone(), line]);
-    frame.render_widget(bar_paragraph, layout[4]);
-
-    // Markers
-    let mut ticks_chars: Vec<char> = vec![' '; width];
-    let mut times_chars: Vec<char> = vec![' '; width];
-    let mut labels_chars: Vec<char> = vec![' '; width];
-
-    // Determine styles for editable fields
-    let wake_style = if app_state.input_mode == InputMode::EditingWakeUp {
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(colors.marker)
-    };
 
-    let bed_style = if app_state.input_mode == InputMode::EditingBedTime {
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(colors.marker)
-    };
+    editor.message = None;
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => editor.select_field(false),
+        KeyCode::Down | KeyCode::Char('j') => editor.select_field(true),
+        KeyCode::Left | KeyCode::Char('h') => editor.adjust(-1.0),
+        KeyCode::Right | KeyCode::Char('l') => editor.adjust(1.0),
+        KeyCode::Char('[') => editor.adjust(-10.0),
+        KeyCode::Char(']') => editor.adjust(10.0),
+        KeyCode::Tab | KeyCode::Char('c') => editor.next_channel(),
+        KeyCode::Char('p') => editor.toggle_picker(),
+        KeyCode::Char('m') => editor.toggle_mode(),
+        KeyCode::Char('#') => editor.input = Some(EditorInput::Hex(String::new())),
+        KeyCode::Char('x') => editor.message = editor.clear().err().map(|err| (err, true)),
+        KeyCode::Char('s') => {
+            let name = if theme::get_builtin_themes().iter().any(|t| t.name == editor.theme.name) {
+                format!("{}-custom", editor.theme.name)
+            } else {
+                editor.theme.name.clone()
+            };
+            editor.input = Some(EditorInput::Name(name));
+        },
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app_state.editor = None;
+            app_state.input_mode = InputMode::Normal;
+        },
+        _ => {}
+    }
+}
 
-    let wake_time_display = if app_state.input_mode == InputMode::EditingWakeUp {
-        app_state.input_buffer.clone()
-    } else {
-        format!("{:02}:{:02}", app_state.get_wake_up_seconds()/3600, (app_state.get_wake_up_seconds()%3600)/60)
-    };
+/// Draw whenever what's on screen can change, and otherwise sleep until a
+/// key, a resize, a change to the config, a signal or the next deadline
+fn run_app(terminal: &mut TerminalGuard, app_state: &mut AppState) -> Result<()> {
+    let (sender, events) = mpsc::channel();
+    events::read_input(sender.clone());
+    #[cfg(unix)]
+    events::forward_signals(sender.clone())?;
+    let _watcher = events::watch_files(sender);
+    let mut scheduler = Scheduler::new();
 
-    let bed_time_display = if app_state.input_mode == InputMode::EditingBedTime {
-        app_state.input_buffer.clone()
-    } else {
-        format!("{:02}:{:02}", app_state.get_bed_seconds()/3600, (app_state.get_bed_seconds()%3600)/60)
-    };
+    loop {
+        app_state.record_usage();
+        if app_state.check_reminders() {
+            scheduler.invalidate();
+        }
+        if let Some(next) = app_state.next_reminder() {
+            scheduler.add_timer(next);
+        }
+        scheduler.set_steps(ui::bar_steps(app_state, terminal.size()?.into()));
+        if scheduler.frame_due(&*app_state.clock) {
+            terminal.draw(|f| ui::ui(f, app_state))?;
+        }
 
-    let markers = vec![
-        (app_state.get_wake_up_seconds(), wake_time_display, "Wake Up [w]", wake_style),
-        (12 * 3600, "12:00".to_string(), "Noon", Style::default().fg(colors.marker)),
-        (app_state.get_bed_seconds(), bed_time_display, "Sleep [b]", bed_style),
-    ];
-
-    // We need to render markers manually to support different styles per marker
-    // But since we are using a single string for the line, we can't easily mix styles in the Paragraph for a single line without using Spans.
-    // However, the current implementation builds a String. We should switch to rendering Spans or just use the color for the whole line if we want simplicity, 
-    // but the user wants intuitive editing, so highlighting just the time is better.
-    // Let's stick to the current char-grid approach but we need to know WHICH style to apply to which char.
-    // This is getting complicated for a simple char grid. 
-    // Alternative: Render the editable fields separately? No, they need to be positioned correctly.
-    
-    // Let's use a parallel vector for styles!
-    let mut times_styles: Vec<Style> = vec![Style::default().fg(colors.marker); width];
-
-    for (seconds, time_text, label_text, style) in markers {
-        let pos = (seconds as f64 / total_seconds as f64 * (width as f64 - 1.0)).round() as usize;
-        
-        if pos < width {
-            // Tick
-            ticks_chars[pos] = '│';
-            
-            // Time
-            let t_len = time_text.chars().count();
-            let t_start = if pos >= t_len / 2 { pos - t_len / 2 } else { 0 };
-            let t_start = t_start.min(width.saturating_sub(t_len));
-            if t_start < width {
-                for (i, c) in time_text.chars().enumerate() {
-                    if t_start + i < width {
-                        times_chars[t_start + i] = c;
-                        times_styles[t_start + i] = style;
-                    }
-                }
-            }
+        let event = match scheduler.timeout(&*app_state.clock) {
+            Some(timeout) => match events.recv_timeout(timeout) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            },
+            None => events.recv()?,
+        };
 
-            // Label
-            let l_len = label_text.chars().count();
-            let l_start = if pos >= l_len / 2 { pos - l_len / 2 } else { 0 };
-            let l_start = l_start.min(width.saturating_sub(l_len));
-            if l_start < width {
-                for (i, c) in label_text.chars().enumerate() {
-                    if l_start + i < width {
-                        labels_chars[l_start + i] = c;
-                    }
+        // Handle whatever else has queued up before drawing again, which is
+        // only needed when one of them changed what's on screen
+        let mut changed = false;
+        for event in iter::once(event).chain(events.try_iter()) {
+            match event {
+                #[cfg(unix)]
+                AppEvent::Key(key)
+                    if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    terminal.suspend()?;
+                    changed = true;
+                }
+                AppEvent::Key(key) => match handle_key(app_state, key) {
+                    KeyOutcome::Quit => return Ok(()),
+                    KeyOutcome::Changed => changed = true,
+                    KeyOutcome::Ignored => {}
+                },
+                AppEvent::Resize => changed = true,
+                AppEvent::FilesChanged => {
+                    app_state.reload();
+                    changed = true;
+                }
+                AppEvent::Quit => return Ok(()),
+                #[cfg(unix)]
+                AppEvent::Suspend => {
+                    terminal.suspend()?;
+                    changed = true;
+                }
+                #[cfg(unix)]
+                AppEvent::Resume => {
+                    terminal.resume()?;
+                    changed = true;
                 }
             }
         }
+        if changed {
+            scheduler.invalidate();
+        }
     }
+}
 
-    let ticks_line: String = ticks_chars.into_iter().collect();
-    // Construct times line with styles
-    let mut times_spans = Vec::new();
-    let mut current_style = times_styles[0];
-    let mut current_text = String::new();
+/// What a key press did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyOutcome {
+    /// Nothing on screen changed
+    Ignored,
+    /// The screen needs drawing again
+    Changed,
+    /// The app should quit
+    Quit,
+}
 
-    for (i, c) in times_chars.iter().enumerate() {
-        if times_styles[i] != current_style {
-            times_spans.push(Span::styled(current_text.clone(), current_style));
-            current_text.clear();
-            current_style = times_styles[i];
+/// Act on a key press, and tell whether it changed anything on screen or
+/// quits the app
+fn handle_key(app_state: &mut AppState, key: KeyEvent) -> KeyOutcome {
+    match app_state.input_mode {
+        InputMode::Normal => match key.code {
+            KeyCode::Char('q') => return KeyOutcome::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return KeyOutcome::Quit,
+            KeyCode::Char('t') => app_state.cycle_theme(),
+            KeyCode::Char('d') => app_state.toggle_mode(),
+            KeyCode::Char('s') => app_state.cycle_style(),
+            KeyCode::Char('l') => app_state.cycle_layout(),
+            KeyCode::Char('r') => app_state.toggle_ruler(),
+            KeyCode::Char('g') => app_state.open_gallery(),
+            KeyCode::Char('e') => app_state.open_editor(),
+            KeyCode::Char('h') => {
+                app_state.input_mode = InputMode::Help;
+            },
+            KeyCode::Char('n') => app_state.open_notifications(),
+            KeyCode::Char('f') => app_state.toggle_focus(),
+            KeyCode::Char('u') => app_state.mark_wake(),
+            KeyCode::Char('z') if !key.modifiers.contains(KeyModifiers::CONTROL) => app_state.mark_bed(),
+            KeyCode::Char('p') => app_state.snooze_reminder(),
+            KeyCode::Char('x') => app_state.dismiss_reminder(),
+            KeyCode::Char(' ') => app_state.hide_night(),
+            KeyCode::Char('S') => {
+                app_state.input_mode = InputMode::Stats;
+            },
+            KeyCode::Char('H') => {
+                app_state.input_mode = InputMode::History;
+                app_state.history_offset = 0;
+            },
+            KeyCode::Char('w') => {
+                app_state.input_mode = InputMode::EditingWakeUp;
+                app_state.input_buffer = app_state.config.wake_up_time.clone();
+                app_state.error_message = None;
+            },
+            KeyCode::Char('b') => {
+                app_state.input_mode = InputMode::EditingBedTime;
+                app_state.input_buffer = app_state.config.bed_time.clone();
+                app_state.error_message = None;
+            },
+            KeyCode::Char('?') => {
+                let _ = open::that("https://github.com/jordan-jakisa/t-meter/blob/main/docs.md");
+                return KeyOutcome::Ignored;
+            },
+            _ => return KeyOutcome::Ignored,
+        },
+        InputMode::Help => match key.code {
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('q') => {
+                app_state.input_mode = InputMode::Normal;
+            },
+            _ => return KeyOutcome::Ignored,
+        },
+        InputMode::Notifications => match key.code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                app_state.input_mode = InputMode::Normal;
+            },
+            _ => return KeyOutcome::Ignored,
+        },
+        InputMode::Stats => match key.code {
+            KeyCode::Esc | KeyCode::Char('S') | KeyCode::Char('q') => {
+                app_state.input_mode = InputMode::Normal;
+            },
+            _ => return KeyOutcome::Ignored,
+        },
+        InputMode::History => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => app_state.history_offset += 1,
+            KeyCode::Down | KeyCode::Char('j') => {
+                app_state.history_offset = app_state.history_offset.saturating_sub(1);
+            },
+            KeyCode::Esc | KeyCode::Char('H') | KeyCode::Char('q') => {
+                app_state.input_mode = InputMode::Normal;
+            },
+            _ => return KeyOutcome::Ignored,
+        },
+        InputMode::Gallery => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => app_state.gallery_step(false),
+            KeyCode::Down | KeyCode::Char('j') => app_state.gallery_step(true),
+            KeyCode::Left | KeyCode::Char('h') => app_state.gallery_mode = ThemeMode::Light,
+            KeyCode::Right | KeyCode::Char('l') => app_state.gallery_mode = ThemeMode::Dark,
+            KeyCode::Enter => app_state.apply_gallery(),
+            KeyCode::Esc | KeyCode::Char('g') | KeyCode::Char('q') => {
+                app_state.input_mode = InputMode::Normal;
+            },
+            _ => return KeyOutcome::Ignored,
+        },
+        InputMode::ThemeEditor => handle_editor_key(app_state, key.code),
+        InputMode::EditingWakeUp | InputMode::EditingBedTime => match key.code {
+            KeyCode::Enter => {
+                match schedule::parse_hm(&app_state.input_buffer) {
+                    Ok(_) => {
+                        match app_state.input_mode {
+                            InputMode::EditingWakeUp => app_state.config.wake_up_time = app_state.input_buffer.clone(),
+                            InputMode::EditingBedTime => app_state.config.bed_time = app_state.input_buffer.clone(),
+                            _ => {}
+                        }
+                        let _ = app_state.config.save();
+                        app_state.input_mode = InputMode::Normal;
+                        app_state.error_message = None;
+                    },
+                    Err(err) => {
+                        app_state.error_message = Some(err);
+                    }
+                }
+                app_state.input_buffer.clear();
+            },
+            KeyCode::Esc => {
+                app_state.input_mode = InputMode::Normal;
+                app_state.input_buffer.clear();
+                app_state.error_message = None;
+            },
+            KeyCode::Backspace => {
+                app_state.input_buffer.pop();
+            },
+            KeyCode::Char(c) if c.is_ascii_digit() || c == ':' => {
+                app_state.input_buffer.push(c);
+            },
+            _ => return KeyOutcome::Ignored,
         }
-        current_text.push(*c);
     }
-    times_spans.push(Span::styled(current_text, current_style));
-
-    let labels_line: String = labels_chars.into_iter().collect();
-
-    frame.render_widget(Paragraph::new(ticks_line).style(Style::default().fg(colors.marker)), layout[5]);
-    frame.render_widget(Paragraph::new(Line::from(times_spans)), layout[6]);
-    frame.render_widget(Paragraph::new(labels_line).style(Style::default().fg(colors.marker_label)), layout[7]);
-
-    // Help Text and Error Messages
-    if app_state.input_mode != InputMode::Normal && app_state.input_mode != InputMode::Help {
-        let help_text = if let Some(ref error) = app_state.error_message {
-            format!("❌ Error: {} | Esc to cancel", error)
-        } else {
-            "Enter time (HH:MM) | Enter to confirm | Esc to cancel".to_string()
-        };
-        
-        let text_color = if app_state.error_message.is_some() {
-            Color::Red
-        } else {
-            Color::Yellow
-        };
-        
-        let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(text_color).add_modifier(Modifier::BOLD))
-            .alignment(Alignment::Center);
-        let area = Rect::new(0, frame.area().height - 1, frame.area().width, 1);
-        frame.render_widget(help_paragraph, area);
-    }
-
-    // Zen Quotes
-    let quotes = quotes::get_quotes();
-    let quote_index = (now.hour() as usize) % quotes.len();
-    let quote = &quotes[quote_index];
-    
-    let quote_widget = Paragraph::new(format!("\"{}\"\n~ {}", quote.text, quote.author))
-        .style(Style::default().add_modifier(Modifier::ITALIC).fg(colors.quote))
-        .alignment(Alignment::Center)
-        .wrap(ratatui::widgets::Wrap { trim: true });
-    frame.render_widget(quote_widget, layout[9]);
-
-    // Legend
-    let elapsed_seconds = seconds_since_midnight;
-    let remaining_seconds = total_seconds - elapsed_seconds;
-    
-    let elapsed_str = format!("{:02}:{:02}", elapsed_seconds / 3600, (elapsed_seconds % 3600) / 60);
-    let remaining_str = format!("{:02}:{:02}", remaining_seconds / 3600, (remaining_seconds % 3600) / 60);
-
-    let legend_text = vec![
-        Line::from(vec![
-            Span::styled("● Elapsed:   ", Style::default().fg(colors.legend_elapsed).add_modifier(Modifier::BOLD)),
-            Span::raw(elapsed_str),
-        ]),
-        Line::from(vec![
-            Span::styled("○ Remaining: ", Style::default().fg(colors.legend_remaining).add_modifier(Modifier::BOLD)),
-            Span::raw(remaining_str),
-        ]),
-    ];
-    
-    let legend_widget = Paragraph::new(legend_text)
-        .alignment(Alignment::Center);
-    frame.render_widget(legend_widget, layout[10]);
+    KeyOutcome::Changed
 }
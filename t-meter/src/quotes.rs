@@ -1,8 +1,44 @@
+//! Short quotes about time and being present, one shown each hour, and
+//! quieter ones about rest for winding down before bed.
+
+/// A quote and who said it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Quote {
+    /// The quote, without quotation marks
     pub text: &'static str,
+    /// Who said it
     pub author: &'static str,
 }
 
+/// The quote shown during `hour`, from 0 to 23. Each hour of the day gets
+/// a different quote.
+pub fn for_hour(hour: u32) -> Quote {
+    let quotes = get_quotes();
+    quotes[hour as usize % quotes.len()]
+}
+
+/// The wind-down quote shown during `hour`, for the time before bed
+pub fn wind_down_for_hour(hour: u32) -> Quote {
+    let quotes = get_wind_down_quotes();
+    quotes[hour as usize % quotes.len()]
+}
+
+/// Every wind-down quote
+pub fn get_wind_down_quotes() -> Vec<Quote> {
+    vec![
+        Quote { text: "Sleep is the best meditation.", author: "Dalai Lama" },
+        Quote { text: "Finish each day and be done with it. You have done what you could.", author: "Ralph Waldo Emerson" },
+        Quote { text: "Sleep is the golden chain that ties health and our bodies together.", author: "Thomas Dekker" },
+        Quote { text: "Almost everything will work again if you unplug it for a few minutes, including you.", author: "Anne Lamott" },
+        Quote { text: "There is a time for many words, and there is also a time for sleep.", author: "Homer" },
+        Quote { text: "A good laugh and a long sleep are the best cures in the doctor's book.", author: "Irish proverb" },
+        Quote { text: "Each night, when I go to sleep, I die. And the next morning, when I wake up, I am reborn.", author: "Mahatma Gandhi" },
+        Quote { text: "Tired minds don't plan well. Sleep first, plan later.", author: "Walter Reisch" },
+    ]
+}
+
+/// Every quote
 pub fn get_quotes() -> Vec<Quote> {
     vec![
         Quote { text: "Be present in all things and thankful for all things.", author: "Maya Angelou" },
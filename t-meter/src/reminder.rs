@@ -0,0 +1,279 @@
+//! Reminders at the wake-up, noon and bed markers or any other time of day,
+//! and keeping track of which have gone off.
+//!
+//! Reminders only go off while t-meter is running: ones whose time came
+//! while it was closed are not caught up on when it starts again.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::{self, format_hm, Schedule, SECONDS_PER_DAY};
+
+/// How late a reminder may go off, for when the computer was asleep at its
+/// time. Later than this it's skipped.
+pub const GRACE: Duration = Duration::minutes(10);
+
+/// What a reminder is for: one of the markers or a time of day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Target {
+    /// The planned wake-up time
+    Wake,
+    /// Midday
+    Noon,
+    /// The planned bed time
+    Bed,
+    /// A time of day, in seconds since midnight
+    Time(u32),
+}
+
+impl Target {
+    /// Seconds since midnight that the target falls on with `schedule`
+    pub fn seconds(&self, schedule: Schedule) -> u32 {
+        match self {
+            Target::Wake => schedule.wake(),
+            Target::Noon => 12 * 3600,
+            Target::Bed => schedule.bed(),
+            Target::Time(seconds) => *seconds,
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// `wake`, `noon`, `bed` or an `HH:MM` time
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "wake" => Ok(Target::Wake),
+            "noon" => Ok(Target::Noon),
+            "bed" => Ok(Target::Bed),
+            time => schedule::parse_hm(time)
+                .map(Target::Time)
+                .map_err(|err| format!("'{}' is not wake, noon, bed or a time: {}", text, err)),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Wake => f.write_str("wake"),
+            Target::Noon => f.write_str("noon"),
+            Target::Bed => f.write_str("bed"),
+            Target::Time(seconds) => f.write_str(&format_hm(*seconds)),
+        }
+    }
+}
+
+impl TryFrom<String> for Target {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.to_string()
+    }
+}
+
+/// A way of letting the user know a reminder went off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Delivery {
+    /// A banner across the top of the screen, until snoozed or dismissed
+    Banner,
+    /// The terminal bell
+    Bell,
+    /// A desktop notification through the OSC 9 escape sequence, as
+    /// understood by iTerm2, Windows Terminal, kitty and others
+    Osc9,
+    /// A desktop notification through the OSC 777 escape sequence, as
+    /// understood by rxvt-unicode, foot and VTE based terminals
+    Osc777,
+    /// The reminder's `command`
+    Command,
+}
+
+/// Something to be reminded of at a [`Target`], or some minutes before it
+///
+/// ```
+/// use t_meter::reminder::{Reminder, Target};
+/// use t_meter::Schedule;
+///
+/// let reminder: Reminder = toml::from_str("at = \"bed\"\nbefore = 30").unwrap();
+/// assert_eq!(reminder, Reminder::new(Target::Bed).before(30));
+/// assert_eq!(reminder.seconds(Schedule::default()), 22 * 3600 + 30 * 60);
+/// assert_eq!(reminder.text(), "Bed time in 30 min");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Reminder {
+    /// What it's for
+    pub at: Target,
+
+    /// Minutes before the target to go off
+    #[serde(default)]
+    pub before: u32,
+
+    /// What to say, instead of naming the target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// How to let the user know
+    #[serde(default = "default_delivery")]
+    pub delivery: Vec<Delivery>,
+
+    /// Command run through the shell for [`Delivery::Command`], with the
+    /// text of the reminder in `$T_METER_REMINDER`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+fn default_delivery() -> Vec<Delivery> {
+    vec![Delivery::Banner]
+}
+
+impl Reminder {
+    /// A reminder at `at`, shown as a banner
+    pub fn new(at: Target) -> Self {
+        Reminder { at, before: 0, message: None, delivery: default_delivery(), command: None }
+    }
+
+    /// Go off this many minutes before the target
+    pub fn before(mut self, minutes: u32) -> Self {
+        self.before = minutes;
+        self
+    }
+
+    /// Say this instead of naming the target
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Let the user know in these ways
+    pub fn delivery(mut self, delivery: impl Into<Vec<Delivery>>) -> Self {
+        self.delivery = delivery.into();
+        self
+    }
+
+    /// Seconds since midnight the reminder goes off at with `schedule`.
+    /// Whole days in `before` make no difference.
+    pub fn seconds(&self, schedule: Schedule) -> u32 {
+        let before = self.before % (SECONDS_PER_DAY / 60) * 60;
+        (self.at.seconds(schedule) + SECONDS_PER_DAY - before) % SECONDS_PER_DAY
+    }
+
+    /// The message, or what the reminder is for and how soon
+    pub fn text(&self) -> String {
+        if let Some(ref message) = self.message {
+            return message.clone();
+        }
+        let target = match self.at {
+            Target::Wake => "Wake up".to_string(),
+            Target::Noon => "Noon".to_string(),
+            Target::Bed => "Bed time".to_string(),
+            Target::Time(seconds) => format_hm(seconds),
+        };
+        match self.before {
+            0 => target,
+            minutes => format!("{} in {} min", target, minutes),
+        }
+    }
+}
+
+/// Which reminders have gone off, and the ones snoozed until later. It
+/// starts from when it was made, so nothing from before then goes off.
+///
+/// ```
+/// use chrono::{Duration, Local, TimeZone};
+/// use t_meter::reminder::{Reminder, Reminders, Target};
+/// use t_meter::Schedule;
+///
+/// let start = Local.with_ymd_and_hms(2026, 10, 17, 22, 0, 0).unwrap();
+/// let rules = [Reminder::new(Target::Bed).before(30)];
+/// let mut reminders = Reminders::new(start);
+///
+/// let half_past = start + Duration::minutes(30);
+/// assert_eq!(reminders.due(&rules, Schedule::default(), half_past), rules.to_vec());
+/// assert!(reminders.due(&rules, Schedule::default(), half_past).is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reminders {
+    checked: DateTime<Local>,
+    snoozed: Vec<(DateTime<Local>, Reminder)>,
+}
+
+impl Reminders {
+    /// Keep track of reminders from `now` on
+    pub fn new(now: DateTime<Local>) -> Self {
+        Reminders { checked: now, snoozed: Vec::new() }
+    }
+
+    /// The reminders out of `rules` and the snoozed ones whose time came
+    /// since the last check, up to `now`. Ones more than [`GRACE`] late
+    /// are dropped.
+    pub fn due(
+        &mut self,
+        rules: &[Reminder],
+        schedule: Schedule,
+        now: DateTime<Local>,
+    ) -> Vec<Reminder> {
+        let since = self.checked.max(now - GRACE);
+        let mut due: Vec<Reminder> = rules
+            .iter()
+            .filter(|rule| {
+                occurrence_after(since, rule.seconds(schedule)).is_some_and(|at| at <= now)
+            })
+            .cloned()
+            .collect();
+
+        let (snoozed, waiting) = self.snoozed.drain(..).partition(|(until, _)| *until <= now);
+        self.snoozed = waiting;
+        due.extend(
+            snoozed
+                .into_iter()
+                .filter(|(until, _)| *until > now - GRACE)
+                .map(|(_, reminder)| reminder),
+        );
+
+        self.checked = self.checked.max(now);
+        due
+    }
+
+    /// Have `reminder` go off again at `until`
+    pub fn snooze(&mut self, reminder: Reminder, until: DateTime<Local>) {
+        self.snoozed.push((until, reminder));
+    }
+
+    /// The next time one of `rules` or a snoozed reminder goes off after
+    /// `now`, for waking up to deliver it
+    pub fn next(
+        &self,
+        rules: &[Reminder],
+        schedule: Schedule,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let rules = rules.iter().filter_map(|rule| occurrence_after(now, rule.seconds(schedule)));
+        let snoozed = self.snoozed.iter().map(|(until, _)| *until).filter(|until| *until > now);
+        rules.chain(snoozed).min()
+    }
+}
+
+/// The first moment after `after` that is `seconds` past midnight
+fn occurrence_after(after: DateTime<Local>, seconds: u32) -> Option<DateTime<Local>> {
+    let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0)?;
+    (0..=2)
+        .filter_map(|days| {
+            let date = after.date_naive() + Duration::days(days);
+            Local.from_local_datetime(&date.and_time(time)).earliest()
+        })
+        .find(|at| *at > after)
+}
@@ -0,0 +1,338 @@
+//! `t-meter report`: the history as a table of days, written out one day at
+//! a time as CSV for spreadsheets, JSON for scripts or Markdown for reading,
+//! with totals and averages for the days included.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use clap::ValueEnum;
+
+use t_meter::history::{self, Day};
+use t_meter::schedule::format_hm;
+use t_meter::stats::{self, SleepStats, Tally, Timing};
+
+/// Version of the report's columns and summary. It goes up when one is
+/// renamed, removed or changes meaning, not when one is added.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Column names, as the CSV header and JSON keys
+const COLUMNS: [&str; 12] = [
+    "date",
+    "planned_wake",
+    "planned_bed",
+    "woke_up",
+    "went_to_bed",
+    "wake_drift_minutes",
+    "bed_drift_minutes",
+    "slept_minutes",
+    "focus_minutes",
+    "focus_sessions",
+    "first_opened",
+    "last_seen",
+];
+
+/// The same columns as headings in Markdown
+const HEADINGS: [&str; 12] = [
+    "Date",
+    "Plan up",
+    "Plan bed",
+    "Up",
+    "Bed",
+    "Up drift",
+    "Bed drift",
+    "Slept",
+    "Focus",
+    "Sessions",
+    "First open",
+    "Last seen",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// A row per day under a header, without the summary
+    Csv,
+    /// An object with the days and a summary
+    Json,
+    /// A table of days and a summary
+    Markdown,
+}
+
+/// The dates of the days to include, either end left open when `None`
+#[derive(Debug, Clone, Copy)]
+pub struct Range {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl Range {
+    fn contains(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| from <= date) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+enum Value {
+    Text(String),
+    Minutes(Duration),
+    /// Minutes with a sign, later or earlier than planned
+    Drift(Duration),
+    Count(usize),
+    Missing,
+}
+
+impl Value {
+    fn time(time: Option<DateTime<Local>>) -> Value {
+        time.map_or(Value::Missing, |time| Value::Text(time.format("%H:%M").to_string()))
+    }
+
+    fn csv(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::Minutes(duration) | Value::Drift(duration) => minutes(*duration).to_string(),
+            Value::Count(count) => count.to_string(),
+            Value::Missing => String::new(),
+        }
+    }
+
+    fn json(&self) -> String {
+        match self {
+            Value::Text(text) => serde_json::Value::from(text.as_str()).to_string(),
+            Value::Missing => "null".to_string(),
+            _ => self.csv(),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        match self {
+            Value::Minutes(duration) => hm(*duration),
+            Value::Drift(duration) => signed_hm(*duration),
+            _ => self.csv(),
+        }
+    }
+}
+
+/// The values of `day` for each of `COLUMNS`
+fn row(day: &Day, night_before: Option<&Day>) -> [Value; 12] {
+    let optional =
+        |value: Option<Duration>, wrap: fn(Duration) -> Value| value.map_or(Value::Missing, wrap);
+    [
+        Value::Text(day.date.to_string()),
+        Value::Text(format_hm(day.wake)),
+        Value::Text(format_hm(day.bed)),
+        Value::time(day.woke_up),
+        Value::time(day.went_to_bed),
+        optional(stats::wake_drift(day), Value::Drift),
+        optional(stats::bed_drift(day), Value::Drift),
+        optional(night_before.and_then(|before| stats::slept(before, day)), Value::Minutes),
+        Value::Minutes(day.focus_time()),
+        Value::Count(day.focus.len()),
+        Value::time(Some(day.first_opened)),
+        Value::time(Some(day.last_seen)),
+    ]
+}
+
+/// Write the days of the history read from `reader` that fall in `range`,
+/// one at a time as they're read
+pub fn write(
+    reader: impl BufRead,
+    mut out: impl Write,
+    format: ReportFormat,
+    range: Range,
+) -> Result<()> {
+    write_start(&mut out, format, range)?;
+
+    let mut summary = Summary::default();
+    let mut night_before: Option<Day> = None;
+    for day in history::read_days(reader)? {
+        let day = day?;
+        if range.contains(day.date) {
+            let values = row(&day, night_before.as_ref());
+            write_row(&mut out, format, &values, summary.days)?;
+            summary.add(&day);
+        } else if summary.days == 0 {
+            summary.tally.night_before(&day);
+        }
+        night_before = Some(day);
+    }
+
+    write_summary(&mut out, format, &summary)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_start(out: &mut impl Write, format: ReportFormat, range: Range) -> Result<()> {
+    match format {
+        ReportFormat::Csv => writeln!(out, "{}", COLUMNS.join(","))?,
+        ReportFormat::Json => {
+            let date = |date: Option<NaiveDate>| {
+                date.map_or("null".to_string(), |date| format!("\"{}\"", date))
+            };
+            write!(
+                out,
+                "{{\"schema_version\":{},\"from\":{},\"to\":{},\"days\":[",
+                SCHEMA_VERSION,
+                date(range.from),
+                date(range.to)
+            )?;
+        }
+        ReportFormat::Markdown => {
+            writeln!(out, "# t-meter report")?;
+            writeln!(out)?;
+            let from = range.from.map_or("the start".to_string(), |date| date.to_string());
+            let to = range.to.map_or("the end".to_string(), |date| date.to_string());
+            writeln!(out, "From {} to {}.", from, to)?;
+            writeln!(out)?;
+            writeln!(out, "| {} |", HEADINGS.join(" | "))?;
+            writeln!(out, "|{}", " --- |".repeat(HEADINGS.len()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Write one day. `index` counts the days written before it.
+fn write_row(
+    out: &mut impl Write,
+    format: ReportFormat,
+    values: &[Value],
+    index: usize,
+) -> Result<()> {
+    match format {
+        ReportFormat::Csv => {
+            let cells: Vec<String> = values.iter().map(Value::csv).collect();
+            writeln!(out, "{}", cells.join(","))?;
+        }
+        ReportFormat::Json => {
+            let fields: Vec<String> = COLUMNS
+                .iter()
+                .zip(values)
+                .map(|(column, value)| format!("\"{}\":{}", column, value.json()))
+                .collect();
+            let separator = if index == 0 { "" } else { "," };
+            write!(out, "{}\n{{{}}}", separator, fields.join(","))?;
+        }
+        ReportFormat::Markdown => {
+            let cells: Vec<String> = values.iter().map(Value::markdown).collect();
+            writeln!(out, "| {} |", cells.join(" | "))?;
+        }
+    }
+    Ok(())
+}
+
+/// Totals over the days written
+#[derive(Default)]
+struct Summary {
+    days: usize,
+    tally: Tally,
+    focus: Duration,
+    focus_sessions: usize,
+}
+
+impl Summary {
+    fn add(&mut self, day: &Day) {
+        self.days += 1;
+        self.tally.add(day);
+        self.focus += day.focus_time();
+        self.focus_sessions += day.focus.len();
+    }
+}
+
+fn write_summary(out: &mut impl Write, format: ReportFormat, summary: &Summary) -> Result<()> {
+    let sleep = summary.tally.stats(summary.days as u32);
+    match format {
+        ReportFormat::Csv => {}
+        ReportFormat::Json => {
+            let timing = |timing: Option<Timing>| {
+                timing.map_or("null".to_string(), |timing| {
+                    format!(
+                        "{{\"days\":{},\"on_time\":{},\"average_drift_minutes\":{},\"spread_minutes\":{}}}",
+                        timing.days,
+                        timing.on_time,
+                        minutes(timing.drift),
+                        minutes(timing.spread)
+                    )
+                })
+            };
+            let average_slept =
+                sleep.sleep.map_or("null".to_string(), |slept| minutes(slept).to_string());
+            let newline = if summary.days == 0 { "" } else { "\n" };
+            writeln!(
+                out,
+                "{}],\"summary\":{{\"days\":{},\"nights\":{},\"average_slept_minutes\":{},\
+                 \"wake\":{},\"bed\":{},\"focus_minutes\":{},\"focus_sessions\":{}}}}}",
+                newline,
+                summary.days,
+                sleep.nights,
+                average_slept,
+                timing(sleep.wake),
+                timing(sleep.bed),
+                minutes(summary.focus),
+                summary.focus_sessions
+            )?;
+        }
+        ReportFormat::Markdown => {
+            writeln!(out)?;
+            writeln!(out, "## Summary")?;
+            writeln!(out)?;
+            for line in summary_lines(summary, &sleep) {
+                writeln!(out, "-   {}", line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn summary_lines(summary: &Summary, sleep: &SleepStats) -> Vec<String> {
+    let timing = |what: &str, timing: Option<Timing>| match timing {
+        Some(timing) => format!(
+            "**{}**: {} against the plan on average, varying by ±{}; within {} minutes on {} of {}",
+            what,
+            signed_hm(timing.drift),
+            hm(timing.spread),
+            stats::ON_TIME.num_minutes(),
+            timing.on_time,
+            plural(timing.days, "day")
+        ),
+        None => format!("**{}**: not marked", what),
+    };
+
+    let mut lines = vec![format!("**Days**: {}", summary.days)];
+    lines.push(match sleep.sleep {
+        Some(slept) => {
+            format!("**Slept**: {} on average over {}", hm(slept), plural(sleep.nights, "night"))
+        }
+        None => "**Slept**: no nights with both ends marked".to_string(),
+    });
+    lines.push(timing("Got up", sleep.wake));
+    lines.push(timing("Went to bed", sleep.bed));
+    lines.push(format!(
+        "**Focus**: {} in {}",
+        hm(summary.focus),
+        plural(summary.focus_sessions, "session")
+    ));
+    lines
+}
+
+/// `1 day`, `2 days`
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+fn minutes(duration: Duration) -> i64 {
+    (duration.num_seconds() as f64 / 60.0).round() as i64
+}
+
+/// `H:MM` for a length of time
+pub fn hm(duration: Duration) -> String {
+    let minutes = minutes(duration).abs();
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// `+H:MM` when later than planned, `-H:MM` when earlier
+pub fn signed_hm(duration: Duration) -> String {
+    let sign = if minutes(duration) < 0 { '-' } else { '+' };
+    format!("{}{}", sign, hm(duration))
+}
@@ -0,0 +1,99 @@
+//! Times of day as seconds since midnight, and where they fall on a bar.
+
+/// Length of a day in seconds
+pub const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// When the day starts and ends for the user. Bed time may come before
+/// wake-up, for a bed time after midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Schedule {
+    wake: u32,
+    bed: u32,
+}
+
+impl Schedule {
+    /// A schedule from wake-up and bed times in seconds since midnight.
+    /// Times of a day or more wrap around.
+    pub fn new(wake: u32, bed: u32) -> Self {
+        Schedule { wake: wake % SECONDS_PER_DAY, bed: bed % SECONDS_PER_DAY }
+    }
+
+    /// A schedule from `HH:MM` times, as in the config file
+    pub fn parse(wake: &str, bed: &str) -> Result<Self, String> {
+        Ok(Schedule::new(parse_hm(wake)?, parse_hm(bed)?))
+    }
+
+    /// Wake-up time, in seconds since midnight
+    pub fn wake(&self) -> u32 {
+        self.wake
+    }
+
+    /// Bed time, in seconds since midnight
+    pub fn bed(&self) -> u32 {
+        self.bed
+    }
+
+    /// Wake-up and bed time, for `DayBar::markers`
+    ///
+    /// [`DayBar::markers`]: crate::widgets::DayBar::markers
+    pub fn markers(&self) -> [u32; 2] {
+        [self.wake, self.bed]
+    }
+
+    /// Seconds from `seconds` after midnight until the next bed time, 0 at
+    /// bed time itself
+    pub fn until_bed(&self, seconds: u32) -> u32 {
+        (self.bed + SECONDS_PER_DAY - seconds % SECONDS_PER_DAY) % SECONDS_PER_DAY
+    }
+
+    /// Whether `seconds` after midnight falls from bed time up to wake-up,
+    /// when the user should be asleep
+    pub fn is_night(&self, seconds: u32) -> bool {
+        let since_bed = (seconds % SECONDS_PER_DAY + SECONDS_PER_DAY - self.bed) % SECONDS_PER_DAY;
+        since_bed < (self.wake + SECONDS_PER_DAY - self.bed) % SECONDS_PER_DAY
+    }
+}
+
+impl Default for Schedule {
+    /// 07:00 to 23:00
+    fn default() -> Self {
+        Schedule::new(7 * 3600, 23 * 3600)
+    }
+}
+
+/// Seconds since midnight from an `HH:MM` time
+pub fn parse_hm(text: &str) -> Result<u32, String> {
+    let parts: Vec<&str> = text.split(':').collect();
+    if parts.len() != 2 {
+        return Err("Invalid format. Use HH:MM".to_string());
+    }
+
+    let h: u32 = parts[0].parse().map_err(|_| "Invalid hour".to_string())?;
+    let m: u32 = parts[1].parse().map_err(|_| "Invalid minute".to_string())?;
+
+    if h >= 24 {
+        return Err("Hour must be 0-23".to_string());
+    }
+    if m >= 60 {
+        return Err("Minute must be 0-59".to_string());
+    }
+
+    Ok(h * 3600 + m * 60)
+}
+
+/// `HH:MM` for a number of seconds
+pub fn format_hm(seconds: u32) -> String {
+    format!("{:02}:{:02}", seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// How far through the day `seconds` after midnight is, from 0 to 1
+pub fn day_ratio(seconds: u32) -> f64 {
+    seconds as f64 / SECONDS_PER_DAY as f64
+}
+
+/// The cell holding the moment `seconds` after midnight on a bar `cells`
+/// long. The bar, indicator, markers and ruler all position themselves with
+/// this so they line up.
+pub fn cell_at(seconds: u32, cells: usize) -> usize {
+    (seconds as usize * cells / SECONDS_PER_DAY as usize).min(cells.saturating_sub(1))
+}
@@ -0,0 +1,144 @@
+//! When the screen needs drawing again. What t-meter shows only changes
+//! when the minute turns over or the bar fills another step, so instead of
+//! redrawing on a fixed tick the app sleeps until the next such moment, or
+//! until input, a resize or a timer wakes it.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+
+use crate::clock::Clock;
+use crate::schedule::SECONDS_PER_DAY;
+
+/// Shortest wait between frames, so a fast simulated clock doesn't redraw
+/// more often than a terminal can keep up with
+pub const MIN_INTERVAL: StdDuration = StdDuration::from_millis(16);
+
+/// The parts of a moment that what's on screen depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    date: NaiveDate,
+    minute: u32,
+    tick: u64,
+}
+
+/// Works out when the next frame is due and whether a frame would differ
+/// from the last one drawn.
+///
+/// ```
+/// use chrono::{Duration, Local, TimeZone};
+/// use t_meter::clock::ManualClock;
+/// use t_meter::scheduler::Scheduler;
+///
+/// let clock = ManualClock::new(Local.with_ymd_and_hms(2024, 6, 1, 10, 15, 30).unwrap());
+/// let mut scheduler = Scheduler::new();
+/// assert!(scheduler.frame_due(&clock));
+///
+/// // Nothing changes until the minute turns over
+/// assert_eq!(scheduler.timeout(&clock), Some(std::time::Duration::from_secs(30)));
+/// clock.advance(Duration::seconds(10));
+/// assert!(!scheduler.frame_due(&clock));
+/// clock.advance(Duration::seconds(20));
+/// assert!(scheduler.frame_due(&clock));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    steps: u32,
+    timers: Vec<DateTime<Local>>,
+    last: Option<Stamp>,
+}
+
+impl Scheduler {
+    /// A scheduler following the minute only, with no timers set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Follow a bar that shows `steps` fill levels across the day, its
+    /// length in cells times the steps each cell can show. Zero follows
+    /// the minute only.
+    pub fn set_steps(&mut self, steps: u32) {
+        if steps != self.steps {
+            self.steps = steps;
+            self.last = None;
+        }
+    }
+
+    /// Wake up at `at` even if nothing else changes by then. Setting the
+    /// same timer again has no effect.
+    pub fn add_timer(&mut self, at: DateTime<Local>) {
+        if !self.timers.contains(&at) {
+            self.timers.push(at);
+        }
+    }
+
+    /// Draw the next frame whatever the time, such as after input or a
+    /// change of settings
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    /// The first moment after `now` at which the screen can change: the
+    /// next minute, the next step of the bar or the earliest timer
+    pub fn next_deadline(&self, now: DateTime<Local>) -> DateTime<Local> {
+        let seconds = now.num_seconds_from_midnight() as u64;
+        let second_start = now - Duration::nanoseconds(now.nanosecond() as i64);
+        let after = |next: u64| second_start + Duration::seconds((next - seconds) as i64);
+
+        let mut deadline = after(seconds - seconds % 60 + 60);
+        if self.steps > 0 {
+            let ticks = self.ticks();
+            let next = ((tick_at(seconds, ticks) + 1) * SECONDS_PER_DAY as u64).div_ceil(ticks);
+            deadline = deadline.min(after(next));
+        }
+        self.timers.iter().fold(deadline, |deadline, &timer| deadline.min(timer))
+    }
+
+    /// How long to wait, in real time, before the next frame is due on
+    /// `clock`. `None` when the clock stands still, so only input can
+    /// change the screen.
+    pub fn timeout(&self, clock: &dyn Clock) -> Option<StdDuration> {
+        let rate = clock.rate();
+        if rate <= 0.0 {
+            return None;
+        }
+
+        let now = clock.now();
+        let wait = (self.next_deadline(now) - now).to_std().unwrap_or_default();
+        Some(wait.div_f64(rate).max(MIN_INTERVAL))
+    }
+
+    /// Whether a frame drawn now would differ from the last one: the
+    /// scheduler was invalidated, the minute or the bar moved on, or a
+    /// timer went off. Timers that went off are cleared and the frame
+    /// counts as drawn.
+    pub fn frame_due(&mut self, clock: &dyn Clock) -> bool {
+        let now = clock.now();
+        let seconds = now.num_seconds_from_midnight() as u64;
+        let stamp = Stamp {
+            date: now.date_naive(),
+            minute: (seconds / 60) as u32,
+            tick: tick_at(seconds, self.ticks()),
+        };
+
+        let timers = self.timers.len();
+        self.timers.retain(|&timer| timer > now);
+        let due = self.timers.len() < timers || self.last != Some(stamp);
+        self.last = Some(stamp);
+        due
+    }
+
+    /// The bar rounds partly filled cells to the nearest step but places
+    /// the indicator by whole cells, so it can change every half step
+    fn ticks(&self) -> u64 {
+        self.steps as u64 * 2
+    }
+}
+
+/// Which of `ticks` equal parts of the day `seconds` falls in
+fn tick_at(seconds: u64, ticks: u64) -> u64 {
+    if ticks == 0 {
+        return 0;
+    }
+    seconds * ticks / SECONDS_PER_DAY as u64
+}
@@ -0,0 +1,308 @@
+//! Rendering a single frame off-screen and writing it out as SVG, HTML,
+//! ANSI or plain text, for sharing setups and for reproducible screenshots.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+    Terminal,
+};
+
+use t_meter::color;
+
+use crate::ui;
+use crate::AppState;
+
+// Used where neither the theme nor the cell sets a color
+const DEFAULT_BACKGROUND: (u8, u8, u8) = (30, 30, 30);
+const DEFAULT_FOREGROUND: (u8, u8, u8) = (212, 212, 212);
+
+// SVG cell size and font size, in pixels
+const CELL_WIDTH: usize = 9;
+const CELL_HEIGHT: usize = 18;
+const FONT_SIZE: usize = 15;
+
+const FONT_FAMILY: &str = "'DejaVu Sans Mono', Menlo, Consolas, monospace";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotFormat {
+    /// A standalone SVG image
+    Svg,
+    /// An HTML page with the frame in a `<pre>`
+    Html,
+    /// Text with ANSI color escapes, for `cat` in a terminal
+    Ansi,
+    /// Just the characters
+    Text,
+}
+
+/// Draw one frame at the given size
+pub fn render(app_state: &AppState, width: u16, height: u16) -> Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|frame| ui::ui(frame, app_state))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+pub fn export(buffer: &Buffer, format: SnapshotFormat) -> String {
+    match format {
+        SnapshotFormat::Svg => to_svg(buffer),
+        SnapshotFormat::Html => to_html(buffer),
+        SnapshotFormat::Ansi => to_ansi(buffer),
+        SnapshotFormat::Text => to_text(buffer),
+    }
+}
+
+/// A stretch of cells on one row that share a style
+struct Run {
+    column: usize,
+    text: String,
+    /// `None` leaves the color to the terminal or the page default
+    fg: Option<(u8, u8, u8)>,
+    bg: Option<(u8, u8, u8)>,
+    modifier: Modifier,
+}
+
+impl Run {
+    fn fg_or_default(&self) -> (u8, u8, u8) {
+        self.fg.unwrap_or(DEFAULT_FOREGROUND)
+    }
+}
+
+/// Split every row into runs of equally styled cells, with reversed cells
+/// already swapped
+fn rows(buffer: &Buffer) -> Vec<Vec<Run>> {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width.max(1))
+        .map(|cells| {
+            let mut runs: Vec<Run> = Vec::new();
+            for (column, cell) in cells.iter().enumerate() {
+                let (mut fg, mut bg) = (color::to_rgb(cell.fg), color::to_rgb(cell.bg));
+                if cell.modifier.contains(Modifier::REVERSED) {
+                    (fg, bg) = (
+                        Some(bg.unwrap_or(DEFAULT_BACKGROUND)),
+                        Some(fg.unwrap_or(DEFAULT_FOREGROUND)),
+                    );
+                }
+                let modifier = cell.modifier - Modifier::REVERSED;
+
+                match runs.last_mut() {
+                    Some(run) if run.fg == fg && run.bg == bg && run.modifier == modifier => {
+                        run.text.push_str(cell.symbol());
+                    }
+                    _ => runs.push(Run { column, text: cell.symbol().to_string(), fg, bg, modifier }),
+                }
+            }
+            runs
+        })
+        .collect()
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Background of the whole frame: the most common cell background, so a
+/// theme's own background fills the margins too
+fn frame_background(buffer: &Buffer) -> (u8, u8, u8) {
+    let mut counts: Vec<((u8, u8, u8), usize)> = Vec::new();
+    for rgb in buffer.content.iter().filter_map(|cell| color::to_rgb(cell.bg)) {
+        match counts.iter_mut().find(|(color, _)| *color == rgb) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((rgb, 1)),
+        }
+    }
+    counts.into_iter().max_by_key(|(_, count)| *count).map_or(DEFAULT_BACKGROUND, |(rgb, _)| rgb)
+}
+
+fn to_svg(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize * CELL_WIDTH;
+    let height = buffer.area.height as usize * CELL_HEIGHT;
+    let background = frame_background(buffer);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <style>text {{ font-family: {font}; font-size: {size}px; white-space: pre; }}</style>\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{bg}\"/>\n",
+        w = width,
+        h = height,
+        font = FONT_FAMILY,
+        size = FONT_SIZE,
+        bg = hex(background),
+    );
+
+    for (row, runs) in rows(buffer).iter().enumerate() {
+        let y = row * CELL_HEIGHT;
+        let cells = |run: &Run| run.text.chars().count();
+
+        for run in runs.iter().filter(|run| run.bg.is_some_and(|bg| bg != background)) {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                run.column * CELL_WIDTH,
+                y,
+                cells(run) * CELL_WIDTH,
+                CELL_HEIGHT,
+                hex(run.bg.unwrap_or(background)),
+            ));
+        }
+
+        for run in runs.iter().filter(|run| !run.text.trim().is_empty()) {
+            let mut attributes = format!("fill=\"{}\"", hex(run.fg_or_default()));
+            if run.modifier.contains(Modifier::BOLD) {
+                attributes.push_str(" font-weight=\"bold\"");
+            }
+            if run.modifier.contains(Modifier::ITALIC) {
+                attributes.push_str(" font-style=\"italic\"");
+            }
+            if run.modifier.contains(Modifier::DIM) {
+                attributes.push_str(" opacity=\"0.6\"");
+            }
+            if run.modifier.contains(Modifier::UNDERLINED) {
+                attributes.push_str(" text-decoration=\"underline\"");
+            }
+            // textLength keeps glyphs on the cell grid whatever the font
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" {}>{}</text>\n",
+                run.column * CELL_WIDTH,
+                y + CELL_HEIGHT * 3 / 4,
+                cells(run) * CELL_WIDTH,
+                attributes,
+                escape(&run.text),
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn to_html(buffer: &Buffer) -> String {
+    let background = frame_background(buffer);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>t-meter</title>\n\
+         <style>\n\
+         body {{ margin: 0; background: {bg}; }}\n\
+         pre {{ margin: 0; padding: 1em; font-family: {font}; font-size: 14px; line-height: 1.2; \
+         color: {fg}; background: {bg}; }}\n\
+         </style>\n</head>\n<body>\n<pre>",
+        bg = hex(background),
+        fg = hex(DEFAULT_FOREGROUND),
+        font = FONT_FAMILY,
+    );
+
+    for runs in rows(buffer) {
+        for run in runs {
+            let mut style = format!("color: {};", hex(run.fg_or_default()));
+            if let Some(bg) = run.bg.filter(|bg| *bg != background) {
+                style.push_str(&format!(" background: {};", hex(bg)));
+            }
+            if run.modifier.contains(Modifier::BOLD) {
+                style.push_str(" font-weight: bold;");
+            }
+            if run.modifier.contains(Modifier::ITALIC) {
+                style.push_str(" font-style: italic;");
+            }
+            if run.modifier.contains(Modifier::DIM) {
+                style.push_str(" opacity: 0.6;");
+            }
+            if run.modifier.contains(Modifier::UNDERLINED) {
+                style.push_str(" text-decoration: underline;");
+            }
+            html.push_str(&format!("<span style=\"{}\">{}</span>", style, escape(&run.text)));
+        }
+        html.push('\n');
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+/// SGR escapes in the palette the frame was drawn for: the 16 named colors,
+/// the 256-color palette or 24-bit color, as each cell holds them. Colors
+/// left to the terminal stay unset, so `--colors none` has no color codes.
+fn to_ansi(buffer: &Buffer) -> String {
+    let width = (buffer.area.width as usize).max(1);
+    let mut ansi = String::new();
+
+    for cells in buffer.content.chunks(width) {
+        let mut style = None;
+        for cell in cells {
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                style = Some(cell_style);
+                ansi.push_str(&format!("\x1b[{}m", sgr(cell.fg, cell.bg, cell.modifier)));
+            }
+            ansi.push_str(cell.symbol());
+        }
+        ansi.push_str("\x1b[0m\n");
+    }
+
+    ansi
+}
+
+/// The SGR parameters for a cell, starting from a reset
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(sgr_color(fg, 30, 38));
+    codes.extend(sgr_color(bg, 40, 48));
+    codes.join(";")
+}
+
+/// `base` plus the index for the eight normal colors, 60 more for the
+/// bright ones, and `extended` with the palette index or RGB for the rest
+fn sgr_color(color: Color, base: u8, extended: u8) -> Option<String> {
+    let named = |index: u8| Some((base + index).to_string());
+    let bright = |index: u8| Some((base + 60 + index).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(index) => Some(format!("{};5;{}", extended, index)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", extended, r, g, b)),
+    }
+}
+
+/// Characters only, with trailing spaces trimmed
+fn to_text(buffer: &Buffer) -> String {
+    let width = (buffer.area.width as usize).max(1);
+    buffer
+        .content
+        .chunks(width)
+        .map(|cells| {
+            let line: String = cells.iter().map(|cell| cell.symbol()).collect();
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
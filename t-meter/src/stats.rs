@@ -0,0 +1,211 @@
+//! Averages over the sleep log in the [history](crate::history): how far
+//! actual wake-up and bed times drift from the plan, how often they stick
+//! to it, how long the nights were and how regular the times are.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+
+use crate::history::Day;
+use crate::schedule::SECONDS_PER_DAY;
+
+/// How close to the plan a time has to be to count as sticking to it
+pub const ON_TIME: Duration = Duration::minutes(15);
+
+/// How actual times compared with the planned ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Timing {
+    /// Days the time was marked
+    pub days: usize,
+    /// Days it was within [`ON_TIME`] of the plan
+    pub on_time: usize,
+    /// How much later than planned on average, negative when earlier
+    pub drift: Duration,
+    /// Standard deviation of the times of day; the smaller, the more
+    /// consistent
+    pub spread: Duration,
+}
+
+/// The sleep log over a number of days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SleepStats {
+    /// Number of days looked at
+    pub span: u32,
+    /// Getting up, if it was marked at all
+    pub wake: Option<Timing>,
+    /// Going to bed, if it was marked at all
+    pub bed: Option<Timing>,
+    /// Average time from going to bed to getting up the next day
+    pub sleep: Option<Duration>,
+    /// Nights with both going to bed and getting up marked
+    pub nights: usize,
+}
+
+impl SleepStats {
+    /// Stats for the `span` days up to and including `last`, from `days`
+    /// as kept by [`History`](crate::history::History)
+    ///
+    /// ```
+    /// use chrono::Local;
+    /// use t_meter::{history::History, stats::SleepStats};
+    ///
+    /// let history = History::new();
+    /// let week = SleepStats::over(history.days(), Local::now().date_naive(), 7);
+    /// assert_eq!(week.wake, None);
+    /// ```
+    pub fn over(days: &[Day], last: NaiveDate, span: u32) -> Self {
+        let first = last - Duration::days(i64::from(span.max(1)) - 1);
+        let mut tally = Tally::new();
+        if let Some(day) = days.iter().rev().find(|day| day.date < first) {
+            tally.night_before(day);
+        }
+        for day in days.iter().filter(|day| (first..=last).contains(&day.date)) {
+            tally.add(day);
+        }
+        tally.stats(span)
+    }
+}
+
+/// Sleep stats built up a day at a time, for going through a history
+/// without holding all of it
+///
+/// ```
+/// use std::io::Cursor;
+/// use t_meter::{history, stats::Tally};
+///
+/// let mut tally = Tally::new();
+/// for day in history::read_days(Cursor::new("{\"schema_version\":1}\n"))? {
+///     tally.add(&day?);
+/// }
+/// assert_eq!(tally.stats(0).nights, 0);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Tally {
+    wake: Times,
+    bed: Times,
+    slept: i64,
+    nights: usize,
+    night_before: Option<Day>,
+}
+
+impl Tally {
+    /// A tally of no days
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note the day before the first one counted, so the night that
+    /// started on it counts too
+    pub fn night_before(&mut self, day: &Day) {
+        self.night_before = Some(day.clone());
+    }
+
+    /// Count `day`. Days go in date order, so a night is counted on the
+    /// day it ends.
+    pub fn add(&mut self, day: &Day) {
+        if let (Some(woke_up), Some(drift)) = (day.woke_up, wake_drift(day)) {
+            self.wake.add(seconds_from_midnight(woke_up), drift);
+        }
+        if let (Some(went_to_bed), Some(drift)) = (day.went_to_bed, bed_drift(day)) {
+            self.bed.add(seconds_from_midnight(went_to_bed), drift);
+        }
+        if let Some(slept) = self.night_before.as_ref().and_then(|before| slept(before, day)) {
+            self.slept += slept.num_seconds();
+            self.nights += 1;
+        }
+        self.night_before = Some(day.clone());
+    }
+
+    /// The stats of the days counted so far, which cover `span` days
+    pub fn stats(&self, span: u32) -> SleepStats {
+        SleepStats {
+            span,
+            wake: self.wake.timing(),
+            bed: self.bed.timing(),
+            sleep: (self.nights > 0).then(|| seconds(self.slept as f64 / self.nights as f64)),
+            nights: self.nights,
+        }
+    }
+}
+
+/// How much later than planned the user got up on `day`, negative when
+/// earlier
+pub fn wake_drift(day: &Day) -> Option<Duration> {
+    let woke_up = seconds_from_midnight(day.woke_up?);
+    Some(Duration::seconds(wrap(woke_up - i64::from(day.wake))))
+}
+
+/// How much later than planned the user went to bed at the end of `day`,
+/// negative when earlier
+pub fn bed_drift(day: &Day) -> Option<Duration> {
+    let went_to_bed = seconds_from_midnight(day.went_to_bed?);
+    Some(Duration::seconds(wrap(went_to_bed - i64::from(day.bed))))
+}
+
+/// How long the user slept, from going to bed at the end of `night_before`
+/// to getting up on `day`, the day after
+pub fn slept(night_before: &Day, day: &Day) -> Option<Duration> {
+    if day.date.pred_opt() != Some(night_before.date) {
+        return None;
+    }
+    let slept = day.woke_up? - night_before.went_to_bed?;
+    let whole_day = Duration::seconds(SECONDS_PER_DAY.into());
+    (Duration::zero() < slept && slept < whole_day).then_some(slept)
+}
+
+/// Running sums over getting up or going to bed
+#[derive(Debug, Clone, Copy, Default)]
+struct Times {
+    days: usize,
+    on_time: usize,
+    drift: i64,
+    // Offsets are measured from the first time, so times either side of
+    // midnight stay close together
+    first: i64,
+    offsets: i64,
+    squares: i64,
+}
+
+impl Times {
+    fn add(&mut self, actual: i64, drift: Duration) {
+        if self.days == 0 {
+            self.first = actual;
+        }
+        let offset = wrap(actual - self.first);
+        self.days += 1;
+        self.on_time += usize::from(drift.abs() <= ON_TIME);
+        self.drift += drift.num_seconds();
+        self.offsets += offset;
+        self.squares += offset * offset;
+    }
+
+    fn timing(&self) -> Option<Timing> {
+        if self.days == 0 {
+            return None;
+        }
+        let days = self.days as f64;
+        let mean = self.offsets as f64 / days;
+        let variance = (self.squares as f64 / days - mean * mean).max(0.0);
+        Some(Timing {
+            days: self.days,
+            on_time: self.on_time,
+            drift: seconds(self.drift as f64 / days),
+            spread: seconds(variance.sqrt()),
+        })
+    }
+}
+
+fn seconds_from_midnight(time: DateTime<Local>) -> i64 {
+    time.num_seconds_from_midnight().into()
+}
+
+/// A difference between times of day, as the shorter way round the clock
+fn wrap(seconds: i64) -> i64 {
+    let day = i64::from(SECONDS_PER_DAY);
+    (seconds + day / 2).rem_euclid(day) - day / 2
+}
+
+fn seconds(seconds: f64) -> Duration {
+    Duration::seconds(seconds.round() as i64)
+}
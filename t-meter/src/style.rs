@@ -0,0 +1,290 @@
+//! How a progress bar looks: its style and the glyphs each style is drawn
+//! with.
+
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthChar;
+
+/// The look of the progress bar. Serialized by variant name, as in the
+/// `progress_bar_style` config key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum ProgressBarStyle {
+    /// Solid blocks shaded along the gradient
+    Gradient,
+    /// Dense shade for the elapsed part and light shade for the rest
+    Grainy,
+    /// Double lines for the elapsed part and single lines for the rest
+    #[default]
+    Analog,
+    /// Gradient with eighth-block glyphs at the leading edge, so the bar
+    /// moves in steps of 1/8 of a cell
+    Smooth,
+    /// Dots along the gradient, with small dots for the rest of the day
+    Dots,
+    /// Braille cells, filled one dot column (or row, when vertical) at a time
+    Braille,
+    /// Gradient blocks with a gap between hours
+    Hours,
+    /// `[####----]`, for terminals and fonts without box-drawing glyphs
+    Ascii,
+    /// Glyphs taken from `custom_glyphs` in the config
+    Custom,
+}
+
+impl ProgressBarStyle {
+    /// Every style, in the order `cycle()` steps through them
+    pub const ALL: [ProgressBarStyle; 9] = [
+        ProgressBarStyle::Gradient,
+        ProgressBarStyle::Grainy,
+        ProgressBarStyle::Analog,
+        ProgressBarStyle::Smooth,
+        ProgressBarStyle::Dots,
+        ProgressBarStyle::Braille,
+        ProgressBarStyle::Hours,
+        ProgressBarStyle::Ascii,
+        ProgressBarStyle::Custom,
+    ];
+
+    /// The next style in `ALL`, wrapping around
+    pub fn cycle(&self) -> Self {
+        let index = Self::ALL.iter().position(|style| style == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Whether the leading edge shows fractional fill. Such styles don't draw
+    /// the indicator over the bar, since it would hide the partial cell.
+    pub fn is_sub_cell(&self) -> bool {
+        matches!(self, ProgressBarStyle::Smooth | ProgressBarStyle::Braille)
+    }
+
+    /// Whether the filled part is shaded from `progress_start` to `progress_end`
+    pub fn is_gradient(&self) -> bool {
+        matches!(
+            self,
+            ProgressBarStyle::Gradient
+                | ProgressBarStyle::Smooth
+                | ProgressBarStyle::Dots
+                | ProgressBarStyle::Braille
+                | ProgressBarStyle::Hours
+                | ProgressBarStyle::Custom
+        )
+    }
+
+    /// Glyphs for drawing this style along the given axis. Only `Custom`
+    /// borrows from `custom`.
+    pub fn glyphs<'a>(&self, orientation: Orientation, custom: &'a CustomGlyphs) -> BarGlyphs<'a> {
+        let (indicator, marker) = match orientation {
+            Orientation::Horizontal => ("┃", "│"),
+            Orientation::Vertical => ("━", "─"),
+        };
+        let base = |filled, empty| BarGlyphs {
+            filled,
+            empty,
+            partial: &[],
+            partial_inverted: false,
+            partial_backed: false,
+            indicator,
+            marker,
+            separator: None,
+            caps: None,
+        };
+
+        // There are no "upper n/8" blocks, so a column filling from the top
+        // draws the empty part with lower blocks and swaps the colours.
+        match (self, orientation) {
+            (ProgressBarStyle::Gradient, _) => base("█", "█"),
+            (ProgressBarStyle::Grainy, _) => base("▓", "░"),
+            (ProgressBarStyle::Analog, Orientation::Horizontal) => base("║", "│"),
+            (ProgressBarStyle::Analog, Orientation::Vertical) => base("═", "─"),
+            (ProgressBarStyle::Smooth, Orientation::Horizontal) => BarGlyphs {
+                partial: &["▏", "▎", "▍", "▌", "▋", "▊", "▉"],
+                partial_backed: true,
+                ..base("█", "█")
+            },
+            (ProgressBarStyle::Smooth, Orientation::Vertical) => BarGlyphs {
+                partial: &["▇", "▆", "▅", "▄", "▃", "▂", "▁"],
+                partial_inverted: true,
+                ..base("█", "█")
+            },
+            (ProgressBarStyle::Dots, _) => base("●", "·"),
+            (ProgressBarStyle::Braille, Orientation::Horizontal) => {
+                BarGlyphs { partial: &["⣇"], ..base("⣿", "⣀") }
+            }
+            (ProgressBarStyle::Braille, Orientation::Vertical) => {
+                BarGlyphs { partial: &["⡏", "⡟", "⡿"], ..base("⣿", "⡇") }
+            }
+            (ProgressBarStyle::Hours, _) => BarGlyphs { separator: Some(" "), ..base("█", "█") },
+            (ProgressBarStyle::Ascii, Orientation::Horizontal) => BarGlyphs {
+                indicator: "|",
+                marker: "+",
+                caps: Some(("[", "]")),
+                ..base("#", "-")
+            },
+            (ProgressBarStyle::Ascii, Orientation::Vertical) => {
+                BarGlyphs { indicator: "=", marker: "-", ..base("#", ".") }
+            }
+            (ProgressBarStyle::Custom, Orientation::Horizontal) => BarGlyphs {
+                indicator: first_glyph(&custom.indicator, CUSTOM_INDICATOR),
+                marker: first_glyph(&custom.marker, CUSTOM_MARKER),
+                ..base(
+                    first_glyph(&custom.filled, CUSTOM_FILLED),
+                    first_glyph(&custom.empty, CUSTOM_EMPTY),
+                )
+            },
+            (ProgressBarStyle::Custom, Orientation::Vertical) => BarGlyphs {
+                indicator: first_glyph(custom.vertical_indicator.as_deref().unwrap_or(""), indicator),
+                marker: first_glyph(custom.vertical_marker.as_deref().unwrap_or(""), marker),
+                ..base(
+                    first_glyph(&custom.filled, CUSTOM_FILLED),
+                    first_glyph(&custom.empty, CUSTOM_EMPTY),
+                )
+            },
+        }
+    }
+}
+
+/// The first character of a configured glyph, since every glyph fills
+/// exactly one cell. A wide or zero-width character would push the bar out
+/// of line with the markers, so `fallback` stands in for it.
+fn first_glyph<'a>(glyph: &'a str, fallback: &'a str) -> &'a str {
+    match glyph.chars().next() {
+        Some(c) if c.width() == Some(1) => &glyph[..c.len_utf8()],
+        _ => fallback,
+    }
+}
+
+/// Glyphs for the `Custom` progress bar style. Only the first character of
+/// each is used, and only when it is one cell wide.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CustomGlyphs {
+    /// The elapsed part of the day
+    #[serde(default = "default_custom_filled")]
+    pub filled: String,
+
+    /// The rest of the day
+    #[serde(default = "default_custom_empty")]
+    pub empty: String,
+
+    /// The current time
+    #[serde(default = "default_custom_indicator")]
+    pub indicator: String,
+
+    /// Markers such as wake-up and bed time
+    #[serde(default = "default_custom_marker")]
+    pub marker: String,
+
+    /// Indicator for the vertical layout, where it runs across the bar
+    #[serde(default)]
+    pub vertical_indicator: Option<String>,
+
+    /// Marker for the vertical layout, where it runs across the bar
+    #[serde(default)]
+    pub vertical_marker: Option<String>,
+}
+
+// Default custom glyphs, also drawn in place of unusable ones
+const CUSTOM_FILLED: &str = "=";
+const CUSTOM_EMPTY: &str = " ";
+const CUSTOM_INDICATOR: &str = ">";
+const CUSTOM_MARKER: &str = "|";
+
+fn default_custom_filled() -> String {
+    CUSTOM_FILLED.to_string()
+}
+
+fn default_custom_empty() -> String {
+    CUSTOM_EMPTY.to_string()
+}
+
+fn default_custom_indicator() -> String {
+    CUSTOM_INDICATOR.to_string()
+}
+
+fn default_custom_marker() -> String {
+    CUSTOM_MARKER.to_string()
+}
+
+impl CustomGlyphs {
+    /// Config keys whose glyph isn't one cell wide, such as CJK characters
+    /// and most emoji, each with the default glyph drawn in its place
+    ///
+    /// ```
+    /// use t_meter::style::CustomGlyphs;
+    ///
+    /// let mut glyphs = CustomGlyphs::default();
+    /// glyphs.filled = "日".to_string();
+    /// assert_eq!(glyphs.too_wide(), [("filled", "=")]);
+    /// ```
+    pub fn too_wide(&self) -> Vec<(&'static str, &'static str)> {
+        let fields = [
+            ("filled", Some(&self.filled), CUSTOM_FILLED),
+            ("empty", Some(&self.empty), CUSTOM_EMPTY),
+            ("indicator", Some(&self.indicator), CUSTOM_INDICATOR),
+            ("marker", Some(&self.marker), CUSTOM_MARKER),
+            ("vertical_indicator", self.vertical_indicator.as_ref(), "━"),
+            ("vertical_marker", self.vertical_marker.as_ref(), "─"),
+        ];
+        fields
+            .into_iter()
+            .filter_map(|(key, glyph, fallback)| Some((key, glyph?.chars().next()?, fallback)))
+            .filter(|(_, c, _)| c.width() != Some(1))
+            .map(|(key, _, fallback)| (key, fallback))
+            .collect()
+    }
+}
+
+impl Default for CustomGlyphs {
+    fn default() -> Self {
+        CustomGlyphs {
+            filled: default_custom_filled(),
+            empty: default_custom_empty(),
+            indicator: default_custom_indicator(),
+            marker: default_custom_marker(),
+            vertical_indicator: None,
+            vertical_marker: None,
+        }
+    }
+}
+
+/// Direction the day runs in: left-to-right or top-to-bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Midnight on the left
+    Horizontal,
+    /// Midnight at the top
+    Vertical,
+}
+
+/// The characters a progress bar is drawn with. `indicator` marks the
+/// current time and `marker` the wake/bed times, so both cut across the bar.
+///
+/// `partial` holds glyphs for increasing fill levels strictly between empty
+/// and filled, so one cell has `partial.len() + 1` steps. When
+/// `partial_inverted` is set they draw the empty part of the cell instead,
+/// and when `partial_backed` is set the rest of the cell shows the empty
+/// color, for glyph sets whose empty cells are solid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BarGlyphs<'a> {
+    /// A cell of the elapsed part
+    pub filled: &'a str,
+    /// A cell of the rest of the day
+    pub empty: &'a str,
+    /// The cell at the leading edge, by fill level
+    pub partial: &'static [&'static str],
+    /// Whether `partial` glyphs draw the empty part of the cell
+    pub partial_inverted: bool,
+    /// Whether the part of a `partial` cell its glyph leaves blank is
+    /// painted with the empty color
+    pub partial_backed: bool,
+    /// The current time
+    pub indicator: &'a str,
+    /// Markers such as wake-up and bed time
+    pub marker: &'a str,
+    /// Drawn on hour boundaries
+    pub separator: Option<&'a str>,
+    /// Replace the first and last cells
+    pub caps: Option<(&'a str, &'a str)>,
+}
+
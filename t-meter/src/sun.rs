@@ -0,0 +1,38 @@
+//! When the sun sets, for switching to the dark mode at dusk.
+
+use std::f64::consts::PI;
+
+use chrono::{Datelike, NaiveDate};
+
+/// Local sunset time, in seconds since midnight, using the NOAA solar
+/// equations. `longitude` is positive east of Greenwich and `utc_offset` is
+/// the local offset from UTC in seconds. Returns `None` when the sun doesn't
+/// set that day (polar day or night).
+pub fn sunset(date: NaiveDate, latitude: f64, longitude: f64, utc_offset: i32) -> Option<u32> {
+    // Fractional year in radians, taken at noon
+    let gamma = 2.0 * PI / 365.0 * (date.ordinal0() as f64);
+
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    // Hour angle of sunset, allowing for refraction and the sun's radius
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = 90.833_f64.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    let utc_minutes = 720.0 - 4.0 * (longitude - hour_angle) - eqtime;
+    let local_seconds = (utc_minutes * 60.0).round() as i64 + utc_offset as i64;
+    Some(local_seconds.rem_euclid(24 * 60 * 60) as u32)
+}
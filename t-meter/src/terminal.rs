@@ -0,0 +1,119 @@
+//! Taking over the terminal and always giving it back: on a normal exit, on
+//! a panic, when asked to quit by a signal and while suspended with Ctrl+Z.
+
+use std::backtrace::Backtrace;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Stdout, Write};
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use ratatui::crossterm::{
+    cursor::Show,
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use ratatui::prelude::*;
+
+use crate::config::Config;
+
+/// The terminal in raw mode on the alternate screen, put back the way it
+/// was when dropped
+pub struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalGuard {
+    pub fn new() -> Result<Self> {
+        enter()?;
+        let terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        Ok(TerminalGuard { terminal })
+    }
+
+    /// Hand the terminal back to the shell and stop, as Ctrl+Z does outside
+    /// raw mode. Takes the terminal over again once continued.
+    #[cfg(unix)]
+    pub fn suspend(&mut self) -> Result<()> {
+        restore();
+        signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+        self.resume()
+    }
+
+    /// Take the terminal over again and draw the next frame from scratch,
+    /// after something else has had it
+    pub fn resume(&mut self) -> Result<()> {
+        enter()?;
+        // Not `Terminal::clear`, which asks the terminal where the cursor is
+        // and would wait for the input thread to let go of the input
+        execute!(io::stdout(), Clear(ClearType::All))?;
+        self.terminal.swap_buffers();
+        Ok(())
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+fn enter() -> Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    Ok(())
+}
+
+/// Leave raw mode and the alternate screen and show the cursor. Errors are
+/// ignored: this runs on the way out, often when the terminal is already
+/// gone.
+fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+}
+
+/// On a panic, put the terminal back before anything is printed and write
+/// the details to the crash log rather than to the screen
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        restore();
+        match write_crash_log(info) {
+            Some(path) => eprintln!("t-meter crashed. Details were written to {}", path.display()),
+            None => eprintln!("t-meter crashed: {}", info),
+        }
+    }));
+}
+
+/// Append the panic and a backtrace to `crash.log` in the state directory
+fn write_crash_log(info: &PanicHookInfo) -> Option<PathBuf> {
+    let path = Config::state_dir()?.join("crash.log");
+    fs::create_dir_all(path.parent()?).ok()?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path).ok()?;
+    writeln!(
+        file,
+        "--- {} t-meter {}\n{}\n\n{}\n",
+        chrono::Local::now().to_rfc3339(),
+        env!("CARGO_PKG_VERSION"),
+        info,
+        Backtrace::force_capture()
+    )
+    .ok()?;
+    Some(path)
+}
@@ -1,33 +1,270 @@
+//! Themes: a light and a dark `ColorScheme` under one name, and the ones
+//! that ship with t-meter.
+
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use crate::color;
+
+/// Colors for one mode of a theme. In theme files colors are names such as
+/// `"cyan"` or `"#rrggbb"` hex values.
+///
+/// New fields may be added with defaults, so build schemes from
+/// `ColorScheme::default()` or an existing scheme.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct ColorScheme {
+    /// Fills the whole frame. `None` leaves the terminal's own background.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub background: Option<Color>,
+    /// Text without a color of its own
     pub foreground: Color,
+    /// The title
     pub title: Color,
+    /// Start of the gradient over the filled part of the bar
     pub progress_start: Color,
+    /// End of the gradient, and the filled part of styles without one
     pub progress_end: Color,
+    /// The rest of the day
     pub progress_empty: Color,
+    /// The current time on the bar
     pub progress_indicator: Color,
+    /// Wake-up, noon and bed markers
     pub marker: Color,
+    /// Labels under the markers
     pub marker_label: Color,
+    /// The quote of the hour
     pub quote: Color,
+    /// Time elapsed in the legend
     pub legend_elapsed: Color,
+    /// Time remaining in the legend
     pub legend_remaining: Color,
+    /// Background behind the bar, drawn over `background`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bar_background: Option<Color>,
+    /// Background behind the quote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_background: Option<Color>,
+    /// Background behind the legend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legend_background: Option<Color>,
+    /// The time being edited and the edit prompt
+    #[serde(default = "default_highlight")]
+    pub highlight: Color,
+    /// Edit prompt errors
+    #[serde(default = "default_error")]
+    pub error: Color,
+    /// Colors the filled part of the bar passes through. Empty means a
+    /// plain `progress_start` to `progress_end` gradient.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gradient: Vec<GradientStop>,
+    /// How the gradient is laid over the bar
+    #[serde(default)]
+    pub gradient_mode: GradientMode,
+}
+
+impl Default for ColorScheme {
+    /// The light colors of the default theme
+    fn default() -> Self {
+        get_default_theme().light
+    }
+}
+
+fn default_highlight() -> Color {
+    Color::Yellow
+}
+
+fn default_error() -> Color {
+    Color::Red
+}
+
+impl ColorScheme {
+    /// Color of the gradient at `t`, from 0 to 1
+    pub fn gradient_color(&self, t: f64) -> Color {
+        let (first, last) = match (self.gradient.first(), self.gradient.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return color::interpolate(self.progress_start, self.progress_end, t),
+        };
+
+        if t <= first.at {
+            return first.color;
+        }
+
+        for pair in self.gradient.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            if t <= to.at {
+                let span = to.at - from.at;
+                let local = if span > 0.0 { (t - from.at) / span } else { 1.0 };
+                return color::interpolate(from.color, to.color, local);
+            }
+        }
+
+        last.color
+    }
+
+    /// The scheme with every color warmed by `amount`, from 0 to 1, for the
+    /// wind-down before bed. See [`color::warm`].
+    pub fn warmer(&self, amount: f64) -> ColorScheme {
+        let warm = |color: Color| color::warm(color, amount);
+        ColorScheme {
+            background: self.background.map(warm),
+            foreground: warm(self.foreground),
+            title: warm(self.title),
+            progress_start: warm(self.progress_start),
+            progress_end: warm(self.progress_end),
+            progress_empty: warm(self.progress_empty),
+            progress_indicator: warm(self.progress_indicator),
+            marker: warm(self.marker),
+            marker_label: warm(self.marker_label),
+            quote: warm(self.quote),
+            legend_elapsed: warm(self.legend_elapsed),
+            legend_remaining: warm(self.legend_remaining),
+            bar_background: self.bar_background.map(warm),
+            quote_background: self.quote_background.map(warm),
+            legend_background: self.legend_background.map(warm),
+            highlight: warm(self.highlight),
+            error: warm(self.error),
+            gradient: self
+                .gradient
+                .iter()
+                .map(|stop| GradientStop::new(stop.at, warm(stop.color)))
+                .collect(),
+            gradient_mode: self.gradient_mode,
+        }
+    }
+}
+
+/// Colors for the screen shown after bed time. It's kept apart from the
+/// themes so the night is dim whatever the daytime colors are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct NightScheme {
+    /// Fills the whole screen. `None` leaves the terminal's own background.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<Color>,
+    /// The text
+    pub foreground: Color,
+    /// The headline
+    pub accent: Color,
+}
+
+impl Default for NightScheme {
+    /// Dim grey on black
+    fn default() -> Self {
+        NightScheme {
+            background: Some(Color::Rgb(8, 8, 12)),
+            foreground: Color::Rgb(70, 70, 84),
+            accent: Color::Rgb(110, 96, 128),
+        }
+    }
+}
+
+/// A color at a point of a gradient, from 0 to 1. For `GradientMode::Day`
+/// that point is a time of day, so `GradientStop::at_time` is handy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct GradientStop {
+    /// Where the stop is, from 0 to 1
+    pub at: f64,
+    /// The color there
+    pub color: Color,
+}
+
+impl GradientStop {
+    /// A stop at `at`, from 0 to 1
+    pub fn new(at: f64, color: Color) -> Self {
+        GradientStop { at, color }
+    }
+
+    /// A stop at a time of day
+    pub fn at_time(hour: u32, minute: u32, color: Color) -> Self {
+        GradientStop { at: (hour * 60 + minute) as f64 / (24.0 * 60.0), color }
+    }
+}
+
+/// How the gradient is laid over the filled part of the bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum GradientMode {
+    /// Anchored to the 24h axis, so each cell shows the color of its time
+    /// of day and the gradient is revealed as the day goes on
+    #[default]
+    Day,
+    /// Stretched over the filled part, so the whole gradient is always shown
+    Fill,
+    /// One color for the whole filled part: the gradient's color right now
+    Now,
 }
 
+impl GradientMode {
+    /// Position along the gradient of cell `index` out of `cells`, when the
+    /// day is `ratio` through
+    pub fn position(&self, index: usize, cells: usize, ratio: f64) -> f64 {
+        match self {
+            GradientMode::Day => index as f64 / cells as f64,
+            GradientMode::Fill => {
+                let filled = ratio * cells as f64;
+                if filled > 1.0 {
+                    (index as f64 / (filled - 1.0)).min(1.0)
+                } else {
+                    0.0
+                }
+            }
+            GradientMode::Now => ratio,
+        }
+    }
+}
+
+/// Which of a theme's color schemes to use
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum ThemeMode {
+    /// The light scheme
     Light,
+    /// The dark scheme
     Dark,
+    /// Light or dark depending on the time of day or the terminal's
+    /// background; see `ThemeMode::for_time`
+    Auto,
 }
 
 impl ThemeMode {
+    /// The next mode: light, dark, auto and back to light
     pub fn toggle(&self) -> Self {
         match self {
             ThemeMode::Light => ThemeMode::Dark,
-            ThemeMode::Dark => ThemeMode::Light,
+            ThemeMode::Dark => ThemeMode::Auto,
+            ThemeMode::Auto => ThemeMode::Light,
+        }
+    }
+
+    /// Dark from bed time or sunset, whichever comes first, until wake-up;
+    /// light the rest of the day. All times are seconds since midnight, and
+    /// bed time may fall after midnight.
+    pub fn for_time(now: u32, wake: u32, bed: u32, sunset: Option<u32>) -> Self {
+        const DAY: u32 = 24 * 60 * 60;
+        // Measure everything from wake-up so the night never wraps
+        let since_wake = |seconds: u32| (seconds + DAY - wake % DAY) % DAY;
+
+        let dark_from = match sunset {
+            Some(sunset) => since_wake(bed).min(since_wake(sunset)),
+            None => since_wake(bed),
+        };
+
+        if since_wake(now) >= dark_from {
+            ThemeMode::Dark
+        } else {
+            ThemeMode::Light
+        }
+    }
+
+    /// The mode's name, as in the `theme_mode` config key
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => "light",
+            ThemeMode::Dark => "dark",
+            ThemeMode::Auto => "auto",
         }
     }
 }
@@ -39,28 +276,43 @@ impl std::str::FromStr for ThemeMode {
         match s.to_lowercase().as_str() {
             "light" => Ok(ThemeMode::Light),
             "dark" => Ok(ThemeMode::Dark),
+            "auto" => Ok(ThemeMode::Auto),
             _ => Err(format!("Invalid theme mode: {}", s)),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// A named pair of color schemes. User themes are TOML files of this.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Theme {
+    /// Name to pick the theme by, which is also its file name
     pub name: String,
+    /// Colors for the light mode
     pub light: ColorScheme,
+    /// Colors for the dark mode
     pub dark: ColorScheme,
 }
 
 impl Theme {
+    /// A theme from its two color schemes
+    pub fn new(name: impl Into<String>, light: ColorScheme, dark: ColorScheme) -> Self {
+        Theme { name: name.into(), light, dark }
+    }
+
+    /// Colors for a mode. `Auto` should be resolved to light or dark first;
+    /// left unresolved it gets the light colors.
     pub fn get_colors(&self, mode: ThemeMode) -> &ColorScheme {
         match mode {
-            ThemeMode::Light => &self.light,
+            ThemeMode::Light | ThemeMode::Auto => &self.light,
             ThemeMode::Dark => &self.dark,
         }
     }
 }
 
 // Predefined themes
+
+/// The theme used when none is configured
 pub fn get_default_theme() -> Theme {
     Theme {
         name: "default".to_string(),
@@ -68,7 +320,7 @@ pub fn get_default_theme() -> Theme {
             background: None,
             foreground: Color::White,
             title: Color::White,
-            progress_start: Color::White,
+            progress_start: Color::Gray,
             progress_end: Color::White,
             progress_empty: Color::DarkGray,
             progress_indicator: Color::Yellow,
@@ -77,6 +329,13 @@ pub fn get_default_theme() -> Theme {
             quote: Color::Gray,
             legend_elapsed: Color::White,
             legend_remaining: Color::DarkGray,
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Yellow,
+            error: Color::Red,
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
         dark: ColorScheme {
             background: None,
@@ -91,15 +350,23 @@ pub fn get_default_theme() -> Theme {
             quote: Color::Rgb(100, 100, 100),
             legend_elapsed: Color::Cyan,
             legend_remaining: Color::Rgb(60, 60, 60),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Yellow,
+            error: Color::Red,
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
     }
 }
 
+/// Blues and cyans
 pub fn get_ocean_theme() -> Theme {
     Theme {
         name: "ocean".to_string(),
         light: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(240, 248, 255)),
             foreground: Color::Rgb(0, 102, 153),
             title: Color::Rgb(0, 102, 153),
             progress_start: Color::Rgb(0, 153, 204),
@@ -111,9 +378,16 @@ pub fn get_ocean_theme() -> Theme {
             quote: Color::Rgb(102, 153, 179),
             legend_elapsed: Color::Rgb(0, 153, 204),
             legend_remaining: Color::Rgb(153, 204, 229),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(255, 153, 0),
+            error: Color::Rgb(204, 0, 0),
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
         dark: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(0, 20, 40)),
             foreground: Color::Rgb(102, 204, 255),
             title: Color::Rgb(102, 204, 255),
             progress_start: Color::Rgb(51, 153, 255),
@@ -125,15 +399,23 @@ pub fn get_ocean_theme() -> Theme {
             quote: Color::Rgb(77, 128, 153),
             legend_elapsed: Color::Rgb(51, 153, 255),
             legend_remaining: Color::Rgb(51, 102, 153),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(255, 204, 0),
+            error: Color::Rgb(255, 102, 102),
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
     }
 }
 
+/// Greens
 pub fn get_forest_theme() -> Theme {
     Theme {
         name: "forest".to_string(),
         light: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(245, 250, 240)),
             foreground: Color::Rgb(34, 139, 34),
             title: Color::Rgb(34, 139, 34),
             progress_start: Color::Rgb(50, 205, 50),
@@ -145,9 +427,16 @@ pub fn get_forest_theme() -> Theme {
             quote: Color::Rgb(107, 142, 35),
             legend_elapsed: Color::Rgb(50, 205, 50),
             legend_remaining: Color::Rgb(144, 238, 144),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(184, 134, 11),
+            error: Color::Rgb(178, 34, 34),
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
         dark: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(15, 30, 15)),
             foreground: Color::Rgb(144, 238, 144),
             title: Color::Rgb(144, 238, 144),
             progress_start: Color::Rgb(34, 139, 34),
@@ -159,15 +448,23 @@ pub fn get_forest_theme() -> Theme {
             quote: Color::Rgb(85, 107, 47),
             legend_elapsed: Color::Rgb(34, 139, 34),
             legend_remaining: Color::Rgb(60, 90, 60),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(255, 255, 102),
+            error: Color::Rgb(255, 99, 71),
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
     }
 }
 
+/// Oranges and reds
 pub fn get_sunset_theme() -> Theme {
     Theme {
         name: "sunset".to_string(),
         light: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(255, 245, 235)),
             foreground: Color::Rgb(255, 99, 71),
             title: Color::Rgb(255, 99, 71),
             progress_start: Color::Rgb(255, 140, 0),
@@ -179,9 +476,22 @@ pub fn get_sunset_theme() -> Theme {
             quote: Color::Rgb(188, 143, 143),
             legend_elapsed: Color::Rgb(255, 140, 0),
             legend_remaining: Color::Rgb(255, 182, 193),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(199, 21, 133),
+            error: Color::Rgb(178, 34, 34),
+            gradient: vec![
+                GradientStop::at_time(0, 0, Color::Rgb(120, 60, 110)),
+                GradientStop::at_time(6, 0, Color::Rgb(255, 140, 0)),
+                GradientStop::at_time(12, 0, Color::Rgb(255, 200, 60)),
+                GradientStop::at_time(18, 0, Color::Rgb(255, 69, 0)),
+                GradientStop::at_time(24, 0, Color::Rgb(120, 60, 110)),
+            ],
+            gradient_mode: GradientMode::Day,
         },
         dark: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(40, 20, 30)),
             foreground: Color::Rgb(255, 182, 193),
             title: Color::Rgb(255, 182, 193),
             progress_start: Color::Rgb(255, 99, 71),
@@ -193,15 +503,29 @@ pub fn get_sunset_theme() -> Theme {
             quote: Color::Rgb(139, 69, 19),
             legend_elapsed: Color::Rgb(255, 99, 71),
             legend_remaining: Color::Rgb(128, 64, 64),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(255, 215, 0),
+            error: Color::Rgb(255, 80, 80),
+            gradient: vec![
+                GradientStop::at_time(0, 0, Color::Rgb(72, 40, 90)),
+                GradientStop::at_time(6, 0, Color::Rgb(255, 99, 71)),
+                GradientStop::at_time(12, 0, Color::Rgb(255, 190, 90)),
+                GradientStop::at_time(18, 0, Color::Rgb(255, 20, 147)),
+                GradientStop::at_time(24, 0, Color::Rgb(72, 40, 90)),
+            ],
+            gradient_mode: GradientMode::Day,
         },
     }
 }
 
+/// Grays only
 pub fn get_monochrome_theme() -> Theme {
     Theme {
         name: "monochrome".to_string(),
         light: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(250, 250, 250)),
             foreground: Color::Black,
             title: Color::Black,
             progress_start: Color::Rgb(20, 20, 20),
@@ -213,9 +537,16 @@ pub fn get_monochrome_theme() -> Theme {
             quote: Color::Rgb(80, 80, 80),
             legend_elapsed: Color::Rgb(40, 40, 40),
             legend_remaining: Color::Rgb(160, 160, 160),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(0, 0, 0),
+            error: Color::Rgb(120, 0, 0),
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
         dark: ColorScheme {
-            background: None,
+            background: Some(Color::Rgb(10, 10, 10)),
             foreground: Color::White,
             title: Color::White,
             progress_start: Color::Rgb(220, 220, 220),
@@ -227,10 +558,18 @@ pub fn get_monochrome_theme() -> Theme {
             quote: Color::Rgb(180, 180, 180),
             legend_elapsed: Color::Rgb(220, 220, 220),
             legend_remaining: Color::Rgb(100, 100, 100),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(255, 255, 255),
+            error: Color::Rgb(255, 120, 120),
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
     }
 }
 
+/// Maximum contrast, for accessibility
 pub fn get_contrast_theme() -> Theme {
     Theme {
         name: "contrast".to_string(),
@@ -247,6 +586,13 @@ pub fn get_contrast_theme() -> Theme {
             quote: Color::Black,
             legend_elapsed: Color::Blue,
             legend_remaining: Color::Gray,
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Blue,
+            error: Color::Red,
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
         },
         dark: ColorScheme {
             background: Some(Color::Black),
@@ -261,11 +607,80 @@ pub fn get_contrast_theme() -> Theme {
             quote: Color::White,
             legend_elapsed: Color::Cyan,
             legend_remaining: Color::DarkGray,
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Yellow,
+            error: Color::Red,
+            gradient: Vec::new(),
+            gradient_mode: GradientMode::Day,
+        },
+    }
+}
+
+/// Sky colors through the day: night, dawn, noon, dusk and night again
+pub fn get_daylight_theme() -> Theme {
+    Theme {
+        name: "daylight".to_string(),
+        light: ColorScheme {
+            background: Some(Color::Rgb(248, 250, 255)),
+            foreground: Color::Rgb(40, 60, 100),
+            title: Color::Rgb(40, 60, 100),
+            progress_start: Color::Rgb(40, 50, 110),
+            progress_end: Color::Rgb(40, 50, 110),
+            progress_empty: Color::Rgb(215, 222, 235),
+            progress_indicator: Color::Rgb(230, 80, 40),
+            marker: Color::Rgb(40, 60, 100),
+            marker_label: Color::Rgb(70, 90, 130),
+            quote: Color::Rgb(100, 115, 145),
+            legend_elapsed: Color::Rgb(240, 150, 50),
+            legend_remaining: Color::Rgb(150, 165, 195),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(200, 110, 20),
+            error: Color::Rgb(190, 40, 40),
+            gradient: vec![
+                GradientStop::at_time(0, 0, Color::Rgb(40, 50, 110)),
+                GradientStop::at_time(6, 0, Color::Rgb(250, 150, 90)),
+                GradientStop::at_time(12, 0, Color::Rgb(120, 190, 240)),
+                GradientStop::at_time(18, 0, Color::Rgb(240, 110, 120)),
+                GradientStop::at_time(24, 0, Color::Rgb(40, 50, 110)),
+            ],
+            gradient_mode: GradientMode::Day,
+        },
+        dark: ColorScheme {
+            background: Some(Color::Rgb(18, 22, 32)),
+            foreground: Color::Rgb(200, 215, 240),
+            title: Color::Rgb(200, 215, 240),
+            progress_start: Color::Rgb(30, 40, 90),
+            progress_end: Color::Rgb(30, 40, 90),
+            progress_empty: Color::Rgb(35, 40, 55),
+            progress_indicator: Color::Rgb(255, 210, 120),
+            marker: Color::Rgb(170, 185, 215),
+            marker_label: Color::Rgb(120, 135, 170),
+            quote: Color::Rgb(110, 120, 150),
+            legend_elapsed: Color::Rgb(250, 170, 90),
+            legend_remaining: Color::Rgb(70, 80, 110),
+            bar_background: None,
+            quote_background: None,
+            legend_background: None,
+            highlight: Color::Rgb(255, 210, 120),
+            error: Color::Rgb(255, 110, 110),
+            gradient: vec![
+                GradientStop::at_time(0, 0, Color::Rgb(30, 40, 90)),
+                GradientStop::at_time(6, 0, Color::Rgb(230, 120, 70)),
+                GradientStop::at_time(12, 0, Color::Rgb(90, 160, 220)),
+                GradientStop::at_time(18, 0, Color::Rgb(200, 80, 110)),
+                GradientStop::at_time(24, 0, Color::Rgb(30, 40, 90)),
+            ],
+            gradient_mode: GradientMode::Day,
         },
     }
 }
 
-pub fn get_all_themes() -> Vec<Theme> {
+/// Themes shipped with t-meter
+pub fn get_builtin_themes() -> Vec<Theme> {
     vec![
         get_default_theme(),
         get_ocean_theme(),
@@ -273,13 +688,6 @@ pub fn get_all_themes() -> Vec<Theme> {
         get_sunset_theme(),
         get_monochrome_theme(),
         get_contrast_theme(),
+        get_daylight_theme(),
     ]
 }
-
-pub fn get_theme_by_name(name: &str) -> Option<Theme> {
-    get_all_themes().into_iter().find(|t| t.name == name)
-}
-
-pub fn get_theme_names() -> Vec<String> {
-    get_all_themes().iter().map(|t| t.name.clone()).collect()
-}
@@ -0,0 +1,1067 @@
+use chrono::{DateTime, Local, Timelike};
+use ratatui::{
+    layout::Flex,
+    prelude::*,
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+use t_meter::history;
+use t_meter::quotes::{self, Quote};
+use t_meter::schedule::{cell_at, day_ratio, format_hm};
+use t_meter::stats::{SleepStats, Timing};
+use t_meter::style::Orientation;
+use t_meter::theme::{ColorScheme, Theme, ThemeMode};
+use t_meter::widgets::{
+    DayBar, FloatingTime, HelpBox, HistoryChart, Legend, Marker, Markers, QuoteBlock,
+};
+
+use crate::editor::{self, EditorInput, ThemeEditor};
+use crate::config::LayoutMode;
+use crate::logging::{self, Notification};
+use crate::report::{hm, signed_hm};
+use crate::{AppState, InputMode};
+
+// Size thresholds used by `LayoutMode::Auto`
+const FULL_MIN_WIDTH: u16 = 40;
+const FULL_MIN_HEIGHT: u16 = 21;
+const COMPACT_MIN_HEIGHT: u16 = 4;
+const VERTICAL_MAX_WIDTH: u16 = 30;
+const VERTICAL_MIN_HEIGHT: u16 = 12;
+
+// The stats screen: a column for each number of days it averages over, and
+// its rows
+const STATS_SPANS: [u32; 3] = [7, 30, 90];
+const STATS_ROWS: [&str; 6] =
+    ["Up vs plan", "In bed vs plan", "Slept", "Up within", "In bed within", "Nights logged"];
+
+// Rows per theme in the gallery: name, bar, marker times, quote and a gap
+const GALLERY_ENTRY_HEIGHT: u16 = 5;
+
+/// Pick the concrete layout for a frame. Forced layouts are returned as is.
+/// `extra_rows` is added to the height the full layout needs, for the ruler.
+pub fn resolve_layout(mode: LayoutMode, area: Rect, extra_rows: u16) -> LayoutMode {
+    if mode != LayoutMode::Auto {
+        return mode;
+    }
+
+    if area.width >= FULL_MIN_WIDTH && area.height >= FULL_MIN_HEIGHT + extra_rows {
+        LayoutMode::Full
+    } else if area.width < VERTICAL_MAX_WIDTH
+        && area.height >= VERTICAL_MIN_HEIGHT
+        && area.height > area.width
+    {
+        LayoutMode::Vertical
+    } else if area.height >= COMPACT_MIN_HEIGHT {
+        LayoutMode::Compact
+    } else {
+        LayoutMode::Mini
+    }
+}
+
+/// How many fill levels the bar shows across the day at this size: its
+/// length in cells times the steps each cell can show. The screen needs
+/// drawing again whenever the bar moves on a level.
+pub fn bar_steps(app_state: &AppState, area: Rect) -> u32 {
+    let (orientation, cells) = match resolve_layout(app_state.layout_mode, area, ruler_height(app_state)) {
+        LayoutMode::Vertical => (Orientation::Vertical, area.height),
+        _ => (Orientation::Horizontal, area.width),
+    };
+    let glyphs = app_state.progress_bar_style.glyphs(orientation, &app_state.config.custom_glyphs);
+    cells as u32 * (glyphs.partial.len() as u32 + 1)
+}
+
+pub fn ui(frame: &mut Frame, app_state: &AppState) {
+    render(frame, app_state);
+    app_state.color_support.adapt_buffer(frame.buffer_mut());
+}
+
+fn render(frame: &mut Frame, app_state: &AppState) {
+    let warm;
+    let mut colors = app_state.get_colors();
+    if let Some(amount) = app_state.warmth() {
+        warm = colors.warmer(amount);
+        colors = &warm;
+    }
+
+    if let Some(background) = colors.background.filter(|_| !app_state.config.transparent_background) {
+        let style = Style::default().bg(background).fg(colors.foreground);
+        frame.render_widget(Block::default().style(style), frame.area());
+    }
+
+    if app_state.input_mode == InputMode::Help {
+        render_help(frame, colors);
+        return;
+    }
+
+    if app_state.input_mode == InputMode::Notifications {
+        render_notifications(frame, colors);
+        return;
+    }
+
+    if app_state.input_mode == InputMode::Stats {
+        render_stats(frame, app_state, colors);
+        return;
+    }
+
+    if app_state.input_mode == InputMode::History {
+        render_history(frame, app_state, colors);
+        return;
+    }
+
+    let now = app_state.now();
+    let area = frame.area();
+
+    if app_state.input_mode == InputMode::Gallery {
+        render_gallery(frame, app_state, colors, now);
+        return;
+    }
+
+    if app_state.input_mode == InputMode::ThemeEditor {
+        if let Some(ref editor) = app_state.editor {
+            render_editor(frame, app_state, editor, now);
+            return;
+        }
+    }
+
+    if app_state.showing_night() {
+        render_night(frame, app_state);
+        render_banner(frame, app_state, colors);
+        return;
+    }
+
+    match resolve_layout(app_state.layout_mode, area, ruler_height(app_state)) {
+        LayoutMode::Auto | LayoutMode::Full => {
+            render_full(frame, app_state, colors, now);
+            render_status(frame, app_state, colors, now);
+        }
+        LayoutMode::Compact => {
+            render_compact(frame, app_state, colors, now);
+            render_status(frame, app_state, colors, now);
+        }
+        LayoutMode::Mini => render_mini(frame, app_state, colors, now),
+        LayoutMode::Vertical => render_vertical(frame, app_state, colors, now),
+    }
+    render_banner(frame, app_state, colors);
+}
+
+/// The newest reminder that went off across the top row, over everything
+/// else, with the keys to snooze or dismiss it
+fn render_banner(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme) {
+    let Some(reminder) = app_state.banners.last() else {
+        return;
+    };
+    if app_state.input_mode != InputMode::Normal {
+        return;
+    }
+
+    let more = match app_state.banners.len() {
+        1 => String::new(),
+        count => format!(" (+{})", count - 1),
+    };
+    let text = format!(
+        " {}{}   [p] Snooze {} min   [x] Dismiss ",
+        reminder.text(),
+        more,
+        app_state.config.snooze_minutes
+    );
+    let style = Style::default().fg(colors.highlight).add_modifier(Modifier::BOLD | Modifier::REVERSED);
+    let area = frame.area();
+    let row = Rect { height: 1.min(area.height), ..area };
+    frame.render_widget(Paragraph::new(text).style(style).alignment(Alignment::Center), row);
+}
+
+/// The dim screen from bed time until wake-up, in the night colors rather
+/// than the theme's
+fn render_night(frame: &mut Frame, app_state: &AppState) {
+    let night = &app_state.config.night;
+    let area = frame.area();
+    frame.render_widget(Clear, area);
+    if let Some(background) = night.background.filter(|_| !app_state.config.transparent_background) {
+        frame.render_widget(Block::default().style(Style::default().bg(background)), area);
+    }
+
+    let schedule = app_state.schedule();
+    let (bed, wake) = (format_hm(schedule.bed()), format_hm(schedule.wake()));
+    let text = Style::default().fg(night.foreground);
+    let lines = vec![
+        Line::styled("You should be asleep", Style::default().fg(night.accent)),
+        Line::default(),
+        Line::styled(format!("Bed time was {} · Up at {}", bed, wake), text),
+        Line::default(),
+        Line::styled("[Space] Show the day anyway", text),
+    ];
+
+    let [middle] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), middle);
+}
+
+/// The running focus session in the top left corner, the countdown to bed
+/// time while winding down in the middle, and how many warnings came in
+/// since the notifications panel was last open in the top right
+fn render_status(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme, now: DateTime<Local>) {
+    if app_state.input_mode != InputMode::Normal {
+        return;
+    }
+    let area = frame.area();
+    let row = Rect { height: 1.min(area.height), ..area };
+
+    if let Some(left) = app_state.wind_down_left() {
+        let text = format!("☾ Time to sleep in {}", countdown(left));
+        let style = Style::default().fg(colors.highlight);
+        frame.render_widget(Paragraph::new(text).style(style).alignment(Alignment::Center), row);
+    }
+
+    if let Some(start) = app_state.recorder.focus_since() {
+        let minutes = (now - start).num_minutes().max(0);
+        let text = format!(" ● Focus {}:{:02}", minutes / 60, minutes % 60);
+        let style = Style::default().fg(colors.highlight).add_modifier(Modifier::BOLD);
+        frame.render_widget(Paragraph::new(text).style(style), row);
+    }
+
+    let unseen = app_state.unseen_warnings();
+    if unseen > 0 {
+        let text = format!("⚠ {} [n] ", unseen);
+        let style = Style::default().fg(colors.highlight);
+        frame.render_widget(Paragraph::new(text).style(style).alignment(Alignment::Right), row);
+    }
+}
+
+/// `45m` or `1h 05m`, rounding up so the countdown reaches bed time at
+/// `1m` rather than `0m`
+fn countdown(seconds: u32) -> String {
+    let minutes = seconds.div_ceil(60);
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// The quote of the hour, or one about rest while winding down
+fn quote(app_state: &AppState, now: DateTime<Local>) -> Quote {
+    if app_state.wind_down_left().is_some() {
+        quotes::wind_down_for_hour(now.hour())
+    } else {
+        quotes::for_hour(now.hour())
+    }
+}
+
+/// Recent warnings and errors, newest at the bottom, and where to find the
+/// full log
+fn render_notifications(frame: &mut Frame, colors: &ColorScheme) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Notifications
+            Constraint::Length(1), // Log file
+            Constraint::Length(1), // Keys
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("NOTIFICATIONS")
+        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, layout[0]);
+
+    let notifications = logging::recent();
+    let list = Rect { x: layout[2].x + 1, width: layout[2].width.saturating_sub(2), ..layout[2] };
+    if notifications.is_empty() {
+        let empty = Paragraph::new("Nothing to report")
+            .style(Style::default().fg(colors.marker_label))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, list);
+    } else {
+        // As many of the newest as fit once wrapped
+        let width = list.width.max(1) as usize;
+        let mut rows = 0;
+        let mut lines: Vec<Line> = notifications
+            .iter()
+            .rev()
+            .map(|notification| notification_line(notification, colors))
+            .take_while(|line| {
+                rows += line.width().div_ceil(width).max(1);
+                rows <= list.height as usize
+            })
+            .collect();
+        lines.reverse();
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), list);
+    }
+
+    if let Some(path) = logging::log_path() {
+        let log = Paragraph::new(format!("Full log: {}", path.display()))
+            .style(Style::default().fg(colors.marker_label))
+            .alignment(Alignment::Center);
+        frame.render_widget(log, layout[3]);
+    }
+
+    let keys = Paragraph::new("Press [n], [q], or [Esc] to close")
+        .style(Style::default().fg(colors.foreground))
+        .alignment(Alignment::Center);
+    frame.render_widget(keys, layout[4]);
+}
+
+fn notification_line(notification: &Notification, colors: &ColorScheme) -> Line<'static> {
+    let (symbol, color) = match notification.level {
+        log::Level::Error => ("✖", colors.error),
+        _ => ("⚠", colors.highlight),
+    };
+    Line::from(vec![
+        Span::styled(
+            notification.time.format("%H:%M:%S ").to_string(),
+            Style::default().fg(colors.marker_label),
+        ),
+        Span::styled(format!("{} ", symbol), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        // Messages such as parse errors span several lines in the log file
+        Span::styled(
+            notification.message.split_whitespace().collect::<Vec<_>>().join(" "),
+            Style::default().fg(colors.foreground),
+        ),
+    ])
+}
+
+/// How actual wake-up and bed times compare with the plan, averaged over
+/// each of `STATS_SPANS`
+fn render_stats(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Table
+            Constraint::Length(1), // Hint
+            Constraint::Length(1), // Keys
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("SLEEP")
+        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, layout[0]);
+
+    let today = history::waking_day(app_state.now(), app_state.schedule());
+    let days = app_state.recorder.history().days();
+    let stats: Vec<SleepStats> = STATS_SPANS.iter().map(|&span| SleepStats::over(days, today, span)).collect();
+
+    let columns: Vec<[String; 6]> = stats.iter().map(stats_column).collect();
+    let mut header = vec![Span::raw(format!("{:<16}", ""))];
+    header.extend(STATS_SPANS.iter().map(|span| {
+        Span::styled(format!("{:>9}", format!("{} days", span)), Style::default().fg(colors.marker))
+    }));
+    let mut lines = vec![Line::from(header), Line::default()];
+    for (row, label) in STATS_ROWS.iter().enumerate() {
+        let mut spans = vec![Span::styled(format!("{:<16}", label), Style::default().fg(colors.marker_label))];
+        spans.extend(columns.iter().map(|column| {
+            Span::styled(format!("{:>9}", column[row]), Style::default().fg(colors.foreground))
+        }));
+        lines.push(Line::from(spans));
+    }
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), layout[2]);
+
+    let hint = Paragraph::new("Mark getting up with [u] and going to bed with [z]")
+        .style(Style::default().fg(colors.marker_label))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, layout[3]);
+
+    let keys = Paragraph::new("Press [S], [q], or [Esc] to close")
+        .style(Style::default().fg(colors.foreground))
+        .alignment(Alignment::Center);
+    frame.render_widget(keys, layout[4]);
+}
+
+/// The past days as rows of mini-bars on the main bar's axis, today at the
+/// bottom unless scrolled back
+fn render_history(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),                       // Title
+            Constraint::Length(1),                       // Spacer
+            Constraint::Min(0),                          // Days
+            Constraint::Length(ruler_height(app_state)), // Ruler
+            Constraint::Length(1),                       // Spacer
+            Constraint::Length(1),                       // What the cells mean
+            Constraint::Length(1),                       // Keys
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("HISTORY")
+        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, layout[0]);
+
+    let now = app_state.now();
+    let today = history::waking_day(now, app_state.schedule());
+    let last = today - chrono::Duration::days(app_state.history_offset.into());
+    let days = app_state.recorder.history().days();
+    let bar = day_bar(app_state, colors, now.num_seconds_from_midnight());
+    let chart = Rect { x: layout[2].x + 1, width: layout[2].width.saturating_sub(2), ..layout[2] };
+    frame.render_widget(HistoryChart::new(bar, days, last).now(now), chart);
+
+    let width = chart.width.saturating_sub(HistoryChart::LABEL_WIDTH);
+    if app_state.show_ruler && width >= 2 {
+        let ruler = Rect { x: chart.x + HistoryChart::LABEL_WIDTH, width, ..layout[3] };
+        frame.render_widget(Paragraph::new(ruler_lines(colors, width as usize)), ruler);
+    }
+
+    let glyphs = app_state
+        .progress_bar_style
+        .glyphs(Orientation::Horizontal, &app_state.config.custom_glyphs);
+    let entries = [
+        (glyphs.filled, colors.progress_end, "Asleep"),
+        (glyphs.empty, colors.progress_empty, "Awake"),
+        (glyphs.filled, colors.highlight, "Focus"),
+        (glyphs.marker, colors.marker, "Planned"),
+    ];
+    let legend: Vec<Span> = entries
+        .into_iter()
+        .flat_map(|(glyph, color, label)| {
+            [
+                Span::styled(glyph.to_string(), Style::default().fg(color)),
+                Span::styled(format!(" {}   ", label), Style::default().fg(colors.marker_label)),
+            ]
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(legend)).alignment(Alignment::Center), layout[5]);
+
+    let keys = Paragraph::new("[↑↓] Scroll   [H], [q], or [Esc] to close")
+        .style(Style::default().fg(colors.foreground))
+        .alignment(Alignment::Center);
+    frame.render_widget(keys, layout[6]);
+}
+
+/// One column of the stats screen, a value for each of `STATS_ROWS`
+fn stats_column(stats: &SleepStats) -> [String; 6] {
+    let spread = |timing: Timing| format!("±{}", hm(timing.spread));
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "–".to_string());
+    [
+        or_dash(stats.wake.map(|timing| signed_hm(timing.drift))),
+        or_dash(stats.bed.map(|timing| signed_hm(timing.drift))),
+        or_dash(stats.sleep.map(hm)),
+        or_dash(stats.wake.map(spread)),
+        or_dash(stats.bed.map(spread)),
+        format!("{}/{}", stats.nights, stats.span),
+    ]
+}
+
+fn render_help(frame: &mut Frame, colors: &ColorScheme) {
+    let help = HelpBox::new(colors, "HELP")
+        .key("h", "Toggle this help screen")
+        .key("n", "Show recent warnings")
+        .key("f", "Start or stop a focus session")
+        .key("S", "Show sleep stats, [H] past days")
+        .key("q", "Quit application")
+        .gap()
+        .key("t", "Cycle themes")
+        .key("g", "Browse themes")
+        .key("e", "Edit the current theme")
+        .key("d", "Cycle light/dark/auto mode")
+        .key("s", "Cycle progress bar style")
+        .key("l", "Cycle layout")
+        .key("r", "Toggle hour ruler")
+        .gap()
+        .key("w", "Edit wake up time")
+        .key("b", "Edit bed time")
+        .key("u", "Mark getting up now")
+        .key("z", "Mark going to bed now")
+        .key("?", "Open documentation")
+        .gap()
+        .text("Press [h], [q], or [Esc] to close");
+
+    let area = frame.area();
+    frame.render_widget(help, Rect { y: area.y + 1, height: area.height.saturating_sub(1), ..area });
+}
+
+/// Every theme in its light and dark mode side by side, scrolled to keep
+/// the highlighted one in view
+fn render_gallery(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme, now: DateTime<Local>) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Themes
+            Constraint::Length(1), // Keys
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("THEMES")
+        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, layout[0]);
+
+    let keys = Paragraph::new("[↑↓] Theme   [←→] Mode   [Enter] Apply   [Esc] Cancel")
+        .style(Style::default().fg(colors.foreground))
+        .alignment(Alignment::Center);
+    frame.render_widget(keys, layout[3]);
+
+    let list = layout[2];
+    let visible = (list.height / GALLERY_ENTRY_HEIGHT).max(1) as usize;
+    let first = (app_state.gallery_index + 1).saturating_sub(visible);
+
+    for (row, (index, theme)) in app_state.themes.iter().enumerate().skip(first).take(visible).enumerate() {
+        let y = list.y + row as u16 * GALLERY_ENTRY_HEIGHT;
+        let height = (GALLERY_ENTRY_HEIGHT - 1).min(list.bottom().saturating_sub(y));
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(1), // Padding
+                Constraint::Fill(1),   // Light
+                Constraint::Length(2), // Spacer
+                Constraint::Fill(1),   // Dark
+                Constraint::Length(1), // Padding
+            ])
+            .split(Rect::new(list.x, y, list.width, height));
+
+        for (area, mode) in [(columns[1], ThemeMode::Light), (columns[3], ThemeMode::Dark)] {
+            let selected = index == app_state.gallery_index && mode == app_state.gallery_mode;
+            render_preview(frame, app_state, theme, mode, selected, now, area);
+        }
+    }
+}
+
+/// A miniature of one theme and mode: its name, the bar, the marker times
+/// and the start of the quote, on the theme's own background
+fn render_preview(
+    frame: &mut Frame,
+    app_state: &AppState,
+    theme: &Theme,
+    mode: ThemeMode,
+    selected: bool,
+    now: DateTime<Local>,
+    area: Rect,
+) {
+    let colors = theme.get_colors(mode);
+    let width = area.width as usize;
+    if width < 2 {
+        return;
+    }
+
+    let (pointer, name_style) = if selected {
+        ("▶ ", Style::default().fg(colors.highlight).add_modifier(Modifier::BOLD | Modifier::REVERSED))
+    } else {
+        ("  ", Style::default().fg(colors.title).add_modifier(Modifier::BOLD))
+    };
+    let quote = quotes::for_hour(now.hour());
+
+    // The bar and the marker times go in the blank rows
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(pointer, Style::default().fg(colors.highlight)),
+            Span::styled(format!(" {} · {} ", theme.name, mode.as_str()), name_style),
+        ]),
+        Line::default(),
+        Line::default(),
+        Line::styled(
+            format!("\"{}\"", quote.text),
+            Style::default().add_modifier(Modifier::ITALIC).fg(colors.quote),
+        ),
+    ];
+
+    let mut style = Style::default().fg(colors.foreground);
+    if let Some(background) = colors.background {
+        style = style.bg(background);
+    }
+    frame.render_widget(Paragraph::new(lines).style(style), area);
+
+    let row = |offset: u16| Rect { y: area.y + offset, height: 1, ..area }.intersection(area);
+    frame.render_widget(day_bar(app_state, colors, now.num_seconds_from_midnight()), row(1));
+    frame.render_widget(markers(app_state, colors).ticks(false).labels(false), row(2));
+}
+
+/// The bar drawn with the theme being edited, above the list of color fields
+/// and the picker for the selected one
+fn render_editor(frame: &mut Frame, app_state: &AppState, editor: &ThemeEditor, now: DateTime<Local>) {
+    let colors = editor.colors();
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Length(1), // Spacer
+            Constraint::Length(2), // Floating Time
+            Constraint::Length(2), // Bar
+            Constraint::Length(1), // Time Labels
+            Constraint::Length(1), // Quote
+            Constraint::Length(1), // Legend
+            Constraint::Length(1), // Spacer
+            Constraint::Min(3),    // Fields
+            Constraint::Length(4), // Picker
+            Constraint::Length(1), // Message or input
+            Constraint::Length(2), // Keys
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!("EDITING {} · {}", editor.theme.name, editor.mode.as_str()))
+        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, layout[0]);
+
+    let width = layout[3].width as usize;
+    if width < 2 {
+        return;
+    }
+
+    // Live preview
+    let seconds = now.num_seconds_from_midnight();
+    frame.render_widget(FloatingTime::new(colors).at(seconds), layout[2]);
+    frame.render_widget(day_bar(app_state, colors, seconds), layout[3]);
+    frame.render_widget(markers(app_state, colors).ticks(false).labels(false), layout[4]);
+    frame.render_widget(QuoteBlock::new(colors, quotes::for_hour(now.hour())), layout[5]);
+    frame.render_widget(Legend::new(colors).at(seconds), layout[6]);
+
+    // Fields, scrolled to keep the selected one in view
+    let list = layout[8];
+    let visible = (list.height as usize).max(1);
+    let first = (editor.field + 1).saturating_sub(visible);
+    let field_lines: Vec<Line> = (first..editor::field_count(colors).min(first + visible))
+        .map(|index| {
+            let selected = index == editor.field;
+            let value = editor::field(colors, index);
+            let swatch = match value {
+                Some(color) => Span::styled("    ", Style::default().bg(color)),
+                None => Span::raw("    "),
+            };
+            let text = match value {
+                Some(color) => color_hex(color),
+                None => "none".to_string(),
+            };
+            let name_style = if selected {
+                Style::default().fg(colors.highlight).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(colors.foreground)
+            };
+            let mut line = vec![
+                Span::styled(if selected { " ▶ " } else { "   " }, name_style),
+                Span::styled(format!("{:<20}", editor::field_name(colors, index)), name_style),
+                swatch,
+                Span::styled(format!(" {}", text), Style::default().fg(colors.foreground)),
+            ];
+            if let Some(note) = editor::field_note(colors, index) {
+                line.push(Span::styled(format!("  {}", note), Style::default().fg(colors.marker_label)));
+            }
+            Line::from(line)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(field_lines), list);
+
+    frame.render_widget(Paragraph::new(picker_lines(editor, layout[9].width as usize)), layout[9]);
+
+    // Input prompt, or the result of the last action
+    let message = match (&editor.input, &editor.message) {
+        (Some(EditorInput::Hex(buffer)), _) => Some((
+            format!("Hex color: #{}_ | Enter to set | Esc to cancel", buffer.trim_start_matches('#')),
+            colors.highlight,
+        )),
+        (Some(EditorInput::Name(buffer)), _) => Some((
+            format!("Save as: {}_ | Enter to save | Esc to cancel", buffer),
+            colors.highlight,
+        )),
+        (None, Some((text, true))) => Some((format!("❌ {}", text), colors.error)),
+        (None, Some((text, false))) => Some((text.clone(), colors.highlight)),
+        (None, None) => None,
+    };
+    if let Some((text, color)) = message {
+        let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+        frame.render_widget(Paragraph::new(text).style(style).alignment(Alignment::Center), layout[10]);
+    }
+
+    let keys = Paragraph::new(
+        "[↑↓] Field  [←→] Adjust  [[ ]] Adjust by 10  [Tab] Channel  [p] RGB/HSL\n\
+         [m] Light/dark  [#] Hex  [x] Unset  [s] Save  [Esc] Close",
+    )
+    .style(Style::default().fg(colors.marker_label))
+    .alignment(Alignment::Center);
+    frame.render_widget(keys, layout[11]);
+}
+
+/// One row per channel of the picker: the channel's range drawn in the
+/// colors it would give, with the current value marked
+fn picker_lines(editor: &ThemeEditor, width: usize) -> Vec<Line<'static>> {
+    let colors = editor.colors();
+    let values = editor.channel_values();
+    let mode = match editor.picker {
+        editor::Picker::Rgb => "RGB",
+        editor::Picker::Hsl => "HSL",
+    };
+    // "   R " + gauge + " 255"
+    let gauge_width = width.saturating_sub(12).clamp(2, 64);
+
+    let mut lines = vec![Line::styled(
+        format!("   {} picker", mode),
+        Style::default().fg(colors.marker_label),
+    )];
+    for (channel, ((name, max), value)) in editor.picker.channels().into_iter().zip(values).enumerate() {
+        let selected = channel == editor.channel;
+        let label_style = if selected {
+            Style::default().fg(colors.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(colors.foreground)
+        };
+        let current = ((value / max) * (gauge_width - 1) as f64).round() as usize;
+
+        let mut spans = vec![Span::styled(
+            format!("{}{} ", if selected { " ▶ " } else { "   " }, name),
+            label_style,
+        )];
+        spans.extend((0..gauge_width).map(|i| {
+            let color = editor.with_channel(channel, max * i as f64 / (gauge_width - 1) as f64);
+            if i == current {
+                Span::styled("┃", Style::default().fg(colors.foreground).bg(color))
+            } else {
+                Span::styled("━", Style::default().fg(color))
+            }
+        }));
+        spans.push(Span::styled(format!(" {:>3}", value.round() as u32), label_style));
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// `#rrggbb` for a color, or its name when it has no fixed RGB value
+fn color_hex(color: Color) -> String {
+    match t_meter::color::to_rgb(color) {
+        Some((r, g, b)) if matches!(color, Color::Rgb(..)) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => color.to_string(),
+    }
+}
+
+fn render_full(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme, now: DateTime<Local>) {
+    let seconds = now.num_seconds_from_midnight();
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),                       // Top padding
+            Constraint::Length(1),                       // Title
+            Constraint::Length(2),                       // Spacer
+            Constraint::Length(2),                       // Floating Time
+            Constraint::Length(4),                       // Bar
+            Constraint::Length(ruler_height(app_state)), // Ruler
+            Constraint::Length(3),                       // Markers
+            Constraint::Length(2),                       // Spacer
+            Constraint::Length(4),                       // Zen Quote
+            Constraint::Min(1),                          // Legend
+        ])
+        .split(frame.area());
+
+    // Title
+    let title = Paragraph::new("TIME IS FLEETING")
+        .style(Style::default().add_modifier(Modifier::BOLD).fg(colors.title))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, layout[1]);
+
+    let width = layout[4].width as usize;
+    if width < 2 {
+        return;
+    }
+
+    frame.render_widget(FloatingTime::new(colors).at(seconds), layout[3]);
+    frame.render_widget(day_bar(app_state, colors, seconds), layout[4]);
+
+    if app_state.show_ruler {
+        frame.render_widget(Paragraph::new(ruler_lines(colors, width)), layout[5]);
+    }
+
+    frame.render_widget(markers(app_state, colors), layout[6]);
+
+    render_prompt(frame, app_state, colors, false);
+
+    frame.render_widget(QuoteBlock::new(colors, quote(app_state, now)), layout[8]);
+
+    let legend_area = Rect { height: layout[9].height.min(2), ..layout[9] };
+    frame.render_widget(Legend::new(colors).at(seconds), legend_area);
+}
+
+/// Floating time, bar and marker times. Adds a one-line legend and a thicker
+/// bar when there is room for them.
+fn render_compact(
+    frame: &mut Frame,
+    app_state: &AppState,
+    colors: &ColorScheme,
+    now: DateTime<Local>,
+) {
+    let seconds = now.num_seconds_from_midnight();
+    let area = frame.area();
+    let bar_height = if area.height >= 6 { 2 } else { 1 };
+    let legend_height = if area.height >= 5 { 1 } else { 0 };
+    let ruler_height = if area.height >= 8 { ruler_height(app_state) } else { 0 };
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .flex(Flex::Center)
+        .constraints([
+            Constraint::Length(2),             // Floating Time
+            Constraint::Length(bar_height),    // Bar
+            Constraint::Length(ruler_height),  // Ruler
+            Constraint::Length(1),             // Time Labels
+            Constraint::Length(legend_height), // Legend
+        ])
+        .split(area);
+
+    let width = layout[1].width as usize;
+    if width < 2 {
+        return;
+    }
+
+    frame.render_widget(FloatingTime::new(colors).at(seconds), layout[0]);
+    frame.render_widget(day_bar(app_state, colors, seconds), layout[1]);
+
+    if ruler_height > 0 {
+        frame.render_widget(Paragraph::new(ruler_lines(colors, width)), layout[2]);
+    }
+
+    frame.render_widget(markers(app_state, colors).ticks(false).labels(false), layout[3]);
+    frame.render_widget(Legend::new(colors).at(seconds), layout[4]);
+
+    render_prompt(frame, app_state, colors, false);
+}
+
+/// A single line: `HH:MM ▕bar▏ NN%`, or just the time when even that won't fit.
+fn render_mini(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme, now: DateTime<Local>) {
+    let area = frame.area();
+    let row = Rect::new(area.x, area.y + area.height.saturating_sub(1) / 2, area.width, 1.min(area.height));
+
+    if let Some(prompt) = editing_prompt(app_state) {
+        let style = Style::default().fg(prompt_color(app_state, colors)).add_modifier(Modifier::BOLD);
+        frame.render_widget(Paragraph::new(prompt).style(style), row);
+        return;
+    }
+
+    let seconds = now.num_seconds_from_midnight();
+    let ratio = day_ratio(seconds);
+    let time_str = now.format("%H:%M").to_string();
+    let percent_str = format!("{:>3.0}%", ratio * 100.0);
+    let time_style = Style::default().fg(colors.foreground).add_modifier(Modifier::BOLD);
+
+    // "HH:MM " + bar + " NNN%"
+    let bar_width = (row.width as usize).saturating_sub(time_str.len() + percent_str.len() + 2);
+    let line = if bar_width < 2 {
+        Line::from(Span::styled(time_str, time_style))
+    } else {
+        let mut spans = vec![Span::styled(format!("{} ", time_str), time_style)];
+        spans.extend(day_bar(app_state, colors, seconds).line(bar_width).spans);
+        spans.push(Span::styled(format!(" {}", percent_str), Style::default().fg(colors.legend_elapsed)));
+        Line::from(spans)
+    };
+
+    frame.render_widget(Paragraph::new(line).alignment(Alignment::Center), row);
+}
+
+/// The day drawn top-to-bottom like a calendar day view. Markers and the
+/// current time sit beside the bar on their rows, and the current time cuts
+/// across the bar as a horizontal line.
+fn render_vertical(
+    frame: &mut Frame,
+    app_state: &AppState,
+    colors: &ColorScheme,
+    now: DateTime<Local>,
+) {
+    let seconds = now.num_seconds_from_midnight();
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(1), // Padding
+            Constraint::Length(3), // Bar
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Markers and time
+        ])
+        .split(frame.area());
+
+    let bar_area = layout[1];
+    let label_area = layout[3];
+    let rows = bar_area.height as usize;
+    if rows < 2 {
+        return;
+    }
+
+    let markers = marker_list(app_state);
+    let bar = day_bar(app_state, colors, seconds)
+        .orientation(Orientation::Vertical)
+        .markers(markers.iter().filter(|m| !m.is_secondary()).map(Marker::seconds));
+    frame.render_widget(bar, bar_area);
+
+    let time_row = cell_at(seconds, rows);
+    let marker_rows: Vec<usize> = markers.iter().map(|m| cell_at(m.seconds(), rows)).collect();
+    let indicator = app_state
+        .progress_bar_style
+        .glyphs(Orientation::Vertical, &app_state.config.custom_glyphs)
+        .indicator;
+
+    // The current time owns its row. A marker that lands on a taken row moves
+    // to the nearest free one below, or above when it is at the bottom.
+    let mut labels: Vec<Option<Line>> = vec![None; rows];
+    labels[time_row] = Some(Line::from(Span::styled(
+        format!("{} {}", indicator.repeat(2), now.format("%H:%M")),
+        Style::default().fg(colors.foreground).add_modifier(Modifier::BOLD),
+    )));
+
+    for (marker, row) in markers.into_iter().zip(marker_rows) {
+        let free_row = (row..rows).chain((0..row).rev()).find(|r| labels[*r].is_none());
+        if let Some(free_row) = free_row {
+            labels[free_row] = Some(Line::from(vec![
+                Span::styled(marker.time_text().to_string(), marker.time_style(colors)),
+                Span::raw(" "),
+                Span::styled(marker.label().to_string(), Style::default().fg(colors.marker_label)),
+            ]));
+        }
+    }
+
+    let label_lines: Vec<Line> = labels.into_iter().map(Option::unwrap_or_default).collect();
+    frame.render_widget(Paragraph::new(label_lines), label_area);
+
+    render_prompt(frame, app_state, colors, false);
+}
+
+/// The bar in the configured style, showing `seconds` with the wake-up and
+/// bed markers
+fn day_bar<'a>(app_state: &'a AppState, colors: &'a ColorScheme, seconds: u32) -> DayBar<'a> {
+    let mut bar = DayBar::new(colors)
+        .style(app_state.progress_bar_style)
+        .custom_glyphs(&app_state.config.custom_glyphs)
+        .color_support(app_state.color_support)
+        .markers(app_state.schedule().markers())
+        .at(seconds);
+    if let Some(mode) = app_state.config.gradient_mode {
+        bar = bar.gradient_mode(mode);
+    }
+    bar
+}
+
+/// Wake, noon and bed markers, with when the user actually got up and went
+/// to bed beside them. The time being edited shows the input buffer.
+fn marker_list(app_state: &AppState) -> Vec<Marker> {
+    let schedule = app_state.schedule();
+    let shown = |mode: InputMode, marker: Marker| {
+        if app_state.input_mode == mode {
+            marker.time(app_state.input_buffer.clone()).highlighted(true)
+        } else {
+            marker
+        }
+    };
+
+    let mut markers = vec![
+        shown(InputMode::EditingWakeUp, Marker::new(schedule.wake(), "Wake Up [w]")),
+        Marker::new(12 * 3600, "Noon"),
+        shown(InputMode::EditingBedTime, Marker::new(schedule.bed(), "Sleep [b]")),
+    ];
+
+    let (woke_up, went_to_bed) = app_state.recorder.history().marks(app_state.now(), schedule);
+    for (time, label) in [(woke_up, "Up [u]"), (went_to_bed, "Bed [z]")] {
+        if let Some(time) = time {
+            markers.push(Marker::new(time.num_seconds_from_midnight(), label).secondary(true));
+        }
+    }
+    markers
+}
+
+/// Ticks, times and labels for the wake, noon and bed markers
+fn markers<'a>(app_state: &AppState, colors: &'a ColorScheme) -> Markers<'a> {
+    Markers::new(colors, marker_list(app_state))
+}
+
+fn ruler_height(app_state: &AppState) -> u16 {
+    if app_state.show_ruler {
+        2
+    } else {
+        0
+    }
+}
+
+/// Hour ticks with labels underneath. Labels go every 1, 2, 3, 4, 6 or 12
+/// hours, whichever is the closest spacing that keeps them apart, and
+/// quarter-hour (or half-hour) minor ticks appear when there is room.
+fn ruler_lines(colors: &ColorScheme, width: usize) -> Vec<Line<'static>> {
+    const LABEL_WIDTH: usize = 2;
+
+    let mut ticks = vec![' '; width];
+    let minor_minutes = match width / 24 {
+        0 | 1 => None,
+        2 | 3 => Some(30),
+        _ => Some(15),
+    };
+    if let Some(step) = minor_minutes {
+        for minute in (step..24 * 60).step_by(step) {
+            ticks[cell_at(minute as u32 * 60, width)] = '╵';
+        }
+    }
+
+    let major_hours = [1, 2, 3, 4, 6, 12].into_iter().find(|h| width * h / 24 >= 2).unwrap_or(24);
+    for hour in (0..24).step_by(major_hours) {
+        ticks[cell_at(hour * 3600, width)] = '│';
+    }
+
+    // Labels start at their tick; one that would run into the previous
+    // label or off the end is dropped.
+    let label_hours =
+        [1, 2, 3, 4, 6, 12].into_iter().find(|h| width * h / 24 > LABEL_WIDTH).unwrap_or(24);
+    let mut labels = vec![' '; width];
+    let mut next_free = 0;
+    for hour in (0..=24).step_by(label_hours) {
+        let pos = match hour {
+            24 => width.saturating_sub(LABEL_WIDTH),
+            _ => cell_at(hour * 3600, width),
+        };
+        if pos < next_free || pos + LABEL_WIDTH > width {
+            continue;
+        }
+        for (i, c) in format!("{:02}", hour).chars().enumerate() {
+            labels[pos + i] = c;
+        }
+        next_free = pos + LABEL_WIDTH + 1;
+    }
+
+    vec![
+        Line::styled(ticks.into_iter().collect::<String>(), Style::default().fg(colors.marker)),
+        Line::styled(
+            labels.into_iter().collect::<String>(),
+            Style::default().fg(colors.marker_label),
+        ),
+    ]
+}
+
+fn editing_prompt(app_state: &AppState) -> Option<String> {
+    let label = match app_state.input_mode {
+        InputMode::EditingWakeUp => "Wake up",
+        InputMode::EditingBedTime => "Bed time",
+        _ => return None,
+    };
+
+    Some(match app_state.error_message {
+        Some(ref error) => format!("❌ {}: {} | Esc to cancel", label, error),
+        None => format!("{}: {}_ | Enter to confirm | Esc to cancel", label, app_state.input_buffer),
+    })
+}
+
+fn prompt_color(app_state: &AppState, colors: &ColorScheme) -> Color {
+    if app_state.error_message.is_some() {
+        colors.error
+    } else {
+        colors.highlight
+    }
+}
+
+/// Help text and error messages for the time editor, on the bottom row.
+/// Layouts that don't show the marker times ask for the input buffer too.
+fn render_prompt(frame: &mut Frame, app_state: &AppState, colors: &ColorScheme, show_buffer: bool) {
+    let Some(prompt) = editing_prompt(app_state) else {
+        return;
+    };
+
+    let help_text = if show_buffer {
+        prompt
+    } else if let Some(ref error) = app_state.error_message {
+        format!("❌ Error: {} | Esc to cancel", error)
+    } else {
+        "Enter time (HH:MM) | Enter to confirm | Esc to cancel".to_string()
+    };
+
+    let help_paragraph = Paragraph::new(help_text)
+        .style(Style::default().fg(prompt_color(app_state, colors)).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    let area = frame.area();
+    let row = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1.min(area.height));
+    frame.render_widget(help_paragraph, row);
+}
@@ -0,0 +1,190 @@
+//! Keeping the history up to date while the app runs: today's entry, the
+//! schedule in effect, focus sessions and the sleep log, saved to `history.jsonl` in the
+//! data directory every few minutes and on the way out.
+//!
+//! Several t-meters may be open at once. Each merges what the others wrote
+//! into its own history before saving, so none of them erases the others'
+//! marks and focus sessions.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local};
+use t_meter::history::History;
+use t_meter::Schedule;
+
+use crate::config::Config;
+
+// How long changes may sit in memory before they're written out
+const SAVE_INTERVAL: Duration = Duration::minutes(5);
+
+pub struct Recorder {
+    history: History,
+    /// The history as it was last read or written, to tell what others
+    /// changed in the file since
+    base: History,
+    /// Where the history is saved, or `None` to keep it in memory only
+    path: Option<PathBuf>,
+    saved_at: Option<DateTime<Local>>,
+    /// Start of the focus session this t-meter started, which is the only
+    /// one it ends on the way out
+    focus_start: Option<DateTime<Local>>,
+}
+
+impl Recorder {
+    /// A history that is never saved, for a simulated clock or a single
+    /// frame
+    pub fn in_memory() -> Self {
+        Recorder {
+            history: History::new(),
+            base: History::new(),
+            path: None,
+            saved_at: None,
+            focus_start: None,
+        }
+    }
+
+    /// The history from the data directory. When it can't be read it's
+    /// kept in memory only, so a file this build doesn't understand isn't
+    /// overwritten.
+    pub fn open() -> Self {
+        let Some(path) = history_path() else {
+            log::warn!("No data directory found; the history won't be saved");
+            return Self::in_memory();
+        };
+
+        match read(&path) {
+            Ok(history) => {
+                let base = history.clone();
+                let mut history = history;
+                // A session in a file saved this recently may belong to a
+                // t-meter that is still open
+                if !recently_saved(&path) {
+                    history.close_abandoned_focus();
+                }
+                Recorder { history, base, path: Some(path), saved_at: None, focus_start: None }
+            }
+            Err(err) => {
+                log::warn!("Not saving the history, {} can't be read: {}", path.display(), err);
+                Self::in_memory()
+            }
+        }
+    }
+
+    /// Note that the app is open at `now`, saving if it's been a while
+    pub fn record(&mut self, now: DateTime<Local>, schedule: Schedule) {
+        self.history.record(now, schedule);
+        if self.saved_at.is_none_or(|saved_at| now - saved_at >= SAVE_INTERVAL) {
+            self.save(now);
+        }
+    }
+
+    /// Start a focus session, or end the running one
+    pub fn toggle_focus(&mut self, now: DateTime<Local>, schedule: Schedule) {
+        match self.history.end_focus(now) {
+            Some(session) => log::info!(
+                "Focused for {} minutes",
+                session.duration().unwrap_or_default().num_minutes()
+            ),
+            None => self.history.start_focus(now, schedule),
+        }
+        self.focus_start = self.focus_since();
+        self.save(now);
+    }
+
+    /// Note that the user got up at `now`
+    pub fn mark_wake(&mut self, now: DateTime<Local>, schedule: Schedule) {
+        self.history.mark_wake(now, schedule);
+        log::info!("Marked getting up at {}", now.format("%H:%M"));
+        self.save(now);
+    }
+
+    /// Note that the user went to bed at `now`
+    pub fn mark_bed(&mut self, now: DateTime<Local>, schedule: Schedule) {
+        self.history.mark_bed(now, schedule);
+        log::info!("Marked going to bed at {}", now.format("%H:%M"));
+        self.save(now);
+    }
+
+    /// Every day recorded so far
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// When the running focus session started
+    pub fn focus_since(&self) -> Option<DateTime<Local>> {
+        self.history.running_focus().map(|session| session.start)
+    }
+
+    /// Record the last moment the app was open and end the focus session
+    /// it started before quitting. Sessions started by another t-meter are
+    /// left to it.
+    pub fn finish(&mut self, now: DateTime<Local>, schedule: Schedule) {
+        self.history.record(now, schedule);
+        if self.focus_start.is_some() && self.focus_since() == self.focus_start {
+            self.history.end_focus(now);
+        }
+        self.save(now);
+    }
+
+    /// Merge in what other t-meters saved since the last look, then write
+    /// the history next to the old one and move it into place, so it's
+    /// never left half written
+    fn save(&mut self, now: DateTime<Local>) {
+        let Some(ref path) = self.path else {
+            return;
+        };
+        self.saved_at = Some(now);
+
+        let theirs = match read(path) {
+            Ok(theirs) => theirs,
+            Err(err) => {
+                log::warn!("Not saving the history, {} can't be read: {}", path.display(), err);
+                return;
+            }
+        };
+        let mut history = self.history.clone();
+        history.merge(&self.base, &theirs);
+
+        let temp = path.with_file_name(format!("history.{}.jsonl.tmp", std::process::id()));
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| {
+                let mut writer = BufWriter::new(File::create(&temp)?);
+                history.write(&mut writer)?;
+                writer.flush()
+            })
+            .and_then(|()| fs::rename(&temp, path));
+        match result {
+            Ok(()) => {
+                self.base = history.clone();
+                self.history = history;
+            }
+            Err(err) => log::warn!("Failed to save the history to {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// The history in `path`, or none at all when there's no file yet
+fn read(path: &Path) -> io::Result<History> {
+    match File::open(path) {
+        Ok(file) => History::read(BufReader::new(file)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(History::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether the file at `path` was written within two save intervals, as
+/// it would be by a t-meter that is still open
+fn recently_saved(path: &Path) -> bool {
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified());
+    let age = modified.ok().and_then(|modified| modified.elapsed().ok());
+    age.is_some_and(|age| age.as_secs() < 2 * SAVE_INTERVAL.num_seconds() as u64)
+}
+
+/// `history.jsonl` in the data directory
+pub fn history_path() -> Option<PathBuf> {
+    Config::data_dir().map(|dir| dir.join("history.jsonl"))
+}
@@ -0,0 +1,77 @@
+//! The user's own themes: TOML files in the themes directory next to the
+//! config, listed after the built-in ones.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use t_meter::theme::{get_builtin_themes, Theme};
+
+use crate::config::Config;
+
+/// Built-in themes followed by the user's themes from `Config::themes_dir`
+pub fn get_all_themes() -> Vec<Theme> {
+    let mut themes = get_builtin_themes();
+    themes.extend(load_user_themes(&themes));
+    themes
+}
+
+/// Every `*.toml` theme in the themes directory, sorted by name. Files that
+/// can't be read, and themes named after one in `existing`, are skipped
+/// with a warning.
+fn load_user_themes(existing: &[Theme]) -> Vec<Theme> {
+    let Some(entries) = Config::themes_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut themes: Vec<Theme> = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+
+        let theme = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str::<Theme>(&contents).map_err(|e| e.to_string()));
+        match theme {
+            Ok(theme) if existing.iter().chain(&themes).any(|t| t.name == theme.name) => {
+                log::warn!(
+                    "Skipping theme '{}' from {}: the name is already taken",
+                    theme.name,
+                    path.display()
+                );
+            }
+            Ok(theme) => themes.push(theme),
+            Err(e) => log::warn!("Failed to load theme from {}: {}", path.display(), e),
+        }
+    }
+
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Where the user theme called `name` lives. Names that are empty or would
+/// lead out of the themes directory are refused.
+pub fn user_theme_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        bail!("'{}' can't be used as a theme file name", name);
+    }
+    let dir = Config::themes_dir().context("No valid themes directory found")?;
+    Ok(dir.join(format!("{}.toml", name)))
+}
+
+/// Write a theme to the themes directory, replacing any theme file with the
+/// same name. Returns the path written.
+pub fn save_user_theme(theme: &Theme) -> Result<PathBuf> {
+    let path = user_theme_path(&theme.name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create themes directory: {}", dir.display()))?;
+    }
+
+    let toml_str = toml::to_string_pretty(theme).context("Failed to serialize theme")?;
+    fs::write(&path, toml_str)
+        .with_context(|| format!("Failed to write theme file: {}", path.display()))?;
+
+    Ok(path)
+}
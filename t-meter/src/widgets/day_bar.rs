@@ -0,0 +1,389 @@
+//! The day as a progress bar.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use chrono::Timelike;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{StatefulWidget, Widget},
+};
+
+use crate::clock::{Clock, SystemClock};
+use crate::color::ColorSupport;
+use crate::schedule::{cell_at, day_ratio};
+use crate::style::{BarGlyphs, CustomGlyphs, Orientation, ProgressBarStyle};
+use crate::theme::{ColorScheme, GradientMode};
+
+use super::seconds_now;
+
+/// The day as a progress bar, filled up to the current time, with an
+/// indicator at the current time and markers at chosen times of day.
+///
+/// Horizontal bars draw the same row on every line of their area; vertical
+/// ones give each row of their area a slice of the day.
+///
+/// ```
+/// use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+/// use t_meter::{theme, DayBar, Schedule};
+///
+/// let theme = theme::get_default_theme();
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 48, 2));
+/// DayBar::new(&theme.dark)
+///     .at(14 * 3600 + 35 * 60)
+///     .markers(Schedule::default().markers())
+///     .render(buffer.area, &mut buffer);
+/// ```
+///
+/// Rendered as a [`StatefulWidget`] it takes the time from a
+/// [`DayBarState`] instead.
+#[derive(Debug, Clone)]
+pub struct DayBar<'a> {
+    pub(super) colors: &'a ColorScheme,
+    pub(super) style: ProgressBarStyle,
+    custom_glyphs: Cow<'a, CustomGlyphs>,
+    pub(super) gradient_mode: Option<GradientMode>,
+    orientation: Orientation,
+    color_support: ColorSupport,
+    markers: Vec<u32>,
+    time: Option<u32>,
+}
+
+impl<'a> DayBar<'a> {
+    /// A horizontal bar in the default style, drawn with `colors`
+    pub fn new(colors: &'a ColorScheme) -> Self {
+        DayBar {
+            colors,
+            style: ProgressBarStyle::default(),
+            custom_glyphs: Cow::Owned(CustomGlyphs::default()),
+            gradient_mode: None,
+            orientation: Orientation::Horizontal,
+            color_support: ColorSupport::TrueColor,
+            markers: Vec::new(),
+            time: None,
+        }
+    }
+
+    /// The progress bar style
+    pub fn style(mut self, style: ProgressBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Glyphs for `ProgressBarStyle::Custom`
+    pub fn custom_glyphs(mut self, glyphs: &'a CustomGlyphs) -> Self {
+        self.custom_glyphs = Cow::Borrowed(glyphs);
+        self
+    }
+
+    /// How the gradient is laid over the bar, instead of the color scheme's
+    /// `gradient_mode`
+    pub fn gradient_mode(mut self, mode: GradientMode) -> Self {
+        self.gradient_mode = Some(mode);
+        self
+    }
+
+    /// Which way the day runs
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// The palette the terminal can show. Without color, filled and empty
+    /// cells are told apart by their glyphs, and rendered cells are adapted
+    /// to the palette.
+    pub fn color_support(mut self, color_support: ColorSupport) -> Self {
+        self.color_support = color_support;
+        self
+    }
+
+    /// Times of day to mark, in seconds since midnight
+    pub fn markers(mut self, markers: impl IntoIterator<Item = u32>) -> Self {
+        self.markers = markers.into_iter().collect();
+        self
+    }
+
+    /// Show this time, in seconds since midnight, instead of the current
+    /// local time
+    pub fn at(mut self, seconds: u32) -> Self {
+        self.time = Some(seconds);
+        self
+    }
+
+    /// One row of a horizontal bar `width` cells long, for putting the bar
+    /// in a line of text. Colors are left as they are.
+    pub fn line(&self, width: usize) -> Line<'static> {
+        self.horizontal_line(width, self.seconds())
+    }
+
+    fn seconds(&self) -> u32 {
+        self.time.unwrap_or_else(seconds_now)
+    }
+
+    /// Glyphs for the style. Without color, filled and empty cells must
+    /// differ by glyph, and inverted partials (which rely on the background
+    /// color) are dropped.
+    pub(super) fn glyphs(&self) -> BarGlyphs<'_> {
+        let mut glyphs = self.style.glyphs(self.orientation, &self.custom_glyphs);
+
+        if self.color_support.is_monochrome() {
+            if glyphs.empty == glyphs.filled {
+                glyphs.empty = "░";
+            }
+            if glyphs.partial_inverted {
+                glyphs.partial = &[];
+            }
+        }
+
+        glyphs
+    }
+
+    fn horizontal_line(&self, width: usize, seconds: u32) -> Line<'static> {
+        let colors = self.colors;
+        let ratio = day_ratio(seconds);
+        let time_pos = cell_at(seconds, width);
+        let marker_cells: Vec<usize> = self.markers.iter().map(|m| cell_at(*m, width)).collect();
+        let glyphs = self.glyphs();
+        let gradient_mode = self.gradient_mode.unwrap_or(colors.gradient_mode);
+        let separators = separator_cells(width);
+
+        let spans: Vec<Span> = (0..width)
+            .map(|i| {
+                if i == time_pos && !self.style.is_sub_cell() {
+                    Span::styled(
+                        glyphs.indicator.to_string(),
+                        Style::default().fg(colors.progress_indicator).add_modifier(Modifier::BOLD),
+                    )
+                } else if let Some((cap, _)) = glyphs.caps.filter(|_| i == 0) {
+                    Span::styled(cap.to_string(), Style::default().fg(colors.foreground))
+                } else if let Some((_, cap)) = glyphs.caps.filter(|_| i == width - 1) {
+                    Span::styled(cap.to_string(), Style::default().fg(colors.foreground))
+                } else if marker_cells.contains(&i) {
+                    Span::styled(
+                        glyphs.marker.to_string(),
+                        Style::default().fg(colors.marker).add_modifier(Modifier::BOLD),
+                    )
+                } else if let Some(separator) = glyphs.separator.filter(|_| separators.contains(&i)) {
+                    Span::raw(separator.to_string())
+                } else {
+                    let (glyph, style) = cell_glyph(
+                        self.style,
+                        glyphs,
+                        colors,
+                        cell_fill(ratio, width, i),
+                        gradient_mode.position(i, width, ratio),
+                    );
+                    Span::styled(glyph.to_string(), style)
+                }
+            })
+            .collect();
+
+        with_bar_background(Line::from(spans), colors)
+    }
+
+    /// One line per row, each `width` cells wide
+    fn vertical_lines(&self, rows: usize, width: usize, seconds: u32) -> Vec<Line<'static>> {
+        let colors = self.colors;
+        let ratio = day_ratio(seconds);
+        let glyphs = self.glyphs();
+        let gradient_mode = self.gradient_mode.unwrap_or(colors.gradient_mode);
+        let separators = separator_cells(rows);
+        let time_row = cell_at(seconds, rows);
+        let marker_rows: Vec<usize> = self.markers.iter().map(|m| cell_at(*m, rows)).collect();
+
+        (0..rows)
+            .map(|row| {
+                let line = if row == time_row && !self.style.is_sub_cell() {
+                    let style =
+                        Style::default().fg(colors.progress_indicator).add_modifier(Modifier::BOLD);
+                    Line::from(Span::styled(glyphs.indicator.repeat(width), style))
+                } else if marker_rows.contains(&row) {
+                    let style = Style::default().fg(colors.marker).add_modifier(Modifier::BOLD);
+                    Line::from(Span::styled(glyphs.marker.repeat(width), style))
+                } else if let Some(separator) = glyphs.separator.filter(|_| separators.contains(&row)) {
+                    Line::from(separator.repeat(width))
+                } else {
+                    let (glyph, style) = cell_glyph(
+                        self.style,
+                        glyphs,
+                        colors,
+                        cell_fill(ratio, rows, row),
+                        gradient_mode.position(row, rows, ratio),
+                    );
+                    Line::from(Span::styled(glyph.repeat(width), style))
+                };
+                with_bar_background(line, colors)
+            })
+            .collect()
+    }
+
+    fn render_at(&self, area: Rect, buf: &mut Buffer, seconds: u32) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+
+        match self.orientation {
+            Orientation::Horizontal => {
+                let line = self.horizontal_line(area.width as usize, seconds);
+                for y in area.top()..area.bottom() {
+                    buf.set_line(area.x, y, &line, area.width);
+                }
+            }
+            Orientation::Vertical => {
+                let lines = self.vertical_lines(area.height as usize, area.width as usize, seconds);
+                for (y, line) in (area.top()..area.bottom()).zip(&lines) {
+                    buf.set_line(area.x, y, line, area.width);
+                }
+            }
+        }
+
+        if self.color_support != ColorSupport::TrueColor {
+            for position in area.positions() {
+                let cell = &mut buf[position];
+                cell.fg = self.color_support.adapt(cell.fg);
+                cell.bg = self.color_support.adapt(cell.bg);
+            }
+        }
+    }
+
+    /// The cell of `area` the current time falls on, along the bar
+    fn indicator(&self, area: Rect, seconds: u32) -> u16 {
+        let cells = match self.orientation {
+            Orientation::Horizontal => area.width,
+            Orientation::Vertical => area.height,
+        };
+        cell_at(seconds, cells as usize) as u16
+    }
+}
+
+impl Widget for DayBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&self, area, buf);
+    }
+}
+
+impl Widget for &DayBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.render_at(area, buf, self.seconds());
+    }
+}
+
+impl StatefulWidget for DayBar<'_> {
+    type State = DayBarState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut DayBarState) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+impl StatefulWidget for &DayBar<'_> {
+    type State = DayBarState;
+
+    /// Draws the state's time, unless the bar was given one with `at`
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut DayBarState) {
+        let seconds = self.time.unwrap_or_else(|| state.clock.now().num_seconds_from_midnight());
+        self.render_at(area, buf, seconds);
+        state.indicator = Some(self.indicator(area, seconds));
+    }
+}
+
+/// State for a [`DayBar`] drawn as a [`StatefulWidget`]: the clock it reads
+/// the time from, and where the time was drawn last
+pub struct DayBarState {
+    clock: Box<dyn Clock>,
+    indicator: Option<u16>,
+}
+
+impl DayBarState {
+    /// State reading the time from `clock`
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        DayBarState { clock: Box::new(clock), indicator: None }
+    }
+
+    /// The clock the bar takes the time from
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Offset along the bar of the current time indicator, from the last
+    /// render; `None` before the first one
+    pub fn indicator(&self) -> Option<u16> {
+        self.indicator
+    }
+}
+
+impl Default for DayBarState {
+    /// State following the real time
+    fn default() -> Self {
+        DayBarState::new(SystemClock)
+    }
+}
+
+impl fmt::Debug for DayBarState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DayBarState").field("indicator", &self.indicator).finish_non_exhaustive()
+    }
+}
+
+/// Cells holding an hour boundary, for styles with separators. Hours are
+/// grouped so that every block stays at least three cells long.
+pub(super) fn separator_cells(cells: usize) -> Vec<usize> {
+    let Some(hours) = [1, 2, 3, 4, 6, 12].into_iter().find(|h| cells * h / 24 >= 3) else {
+        return Vec::new();
+    };
+
+    (hours..24).step_by(hours).map(|h| h * cells / 24).collect()
+}
+
+/// How much of cell `index` out of `cells` is filled at `ratio`, from 0 to 1
+fn cell_fill(ratio: f64, cells: usize, index: usize) -> f64 {
+    (ratio * cells as f64 - index as f64).clamp(0.0, 1.0)
+}
+
+/// Glyph and style for one cell of the bar. `fill` comes from `cell_fill`
+/// and is rounded to the steps the glyph set can show; `t` is the cell's
+/// position along the gradient, from `gradient_mode`.
+pub(super) fn cell_glyph<'a>(
+    style: ProgressBarStyle,
+    glyphs: BarGlyphs<'a>,
+    colors: &ColorScheme,
+    fill: f64,
+    t: f64,
+) -> (&'a str, Style) {
+    let steps = glyphs.partial.len() + 1;
+    let level = (fill * steps as f64).round() as usize;
+    if level == 0 {
+        return (glyphs.empty, Style::default().fg(colors.progress_empty));
+    }
+
+    let color = if style.is_gradient() {
+        colors.gradient_color(t)
+    } else {
+        colors.progress_end
+    };
+
+    if level >= steps {
+        (glyphs.filled, Style::default().fg(color))
+    } else if glyphs.partial_inverted {
+        (glyphs.partial[level - 1], Style::default().fg(colors.progress_empty).bg(color))
+    } else if glyphs.partial_backed {
+        (glyphs.partial[level - 1], Style::default().fg(color).bg(colors.progress_empty))
+    } else {
+        (glyphs.partial[level - 1], Style::default().fg(color))
+    }
+}
+
+/// Put `bar_background` behind the cells of a bar row that don't set their
+/// own background
+pub(super) fn with_bar_background<'a>(mut line: Line<'a>, colors: &ColorScheme) -> Line<'a> {
+    if let Some(background) = colors.bar_background {
+        for span in line.spans.iter_mut().filter(|span| span.style.bg.is_none()) {
+            span.style = span.style.bg(background);
+        }
+    }
+    line
+}
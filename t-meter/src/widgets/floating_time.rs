@@ -0,0 +1,66 @@
+//! The current time above the bar.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::schedule::{cell_at, format_hm};
+use crate::theme::ColorScheme;
+
+use super::seconds_now;
+
+/// The current time centred over where a [`DayBar`] of the same width
+/// draws it, with a pointer underneath. Two rows high.
+///
+/// [`DayBar`]: super::DayBar
+#[derive(Debug, Clone)]
+pub struct FloatingTime<'a> {
+    colors: &'a ColorScheme,
+    time: Option<u32>,
+}
+
+impl<'a> FloatingTime<'a> {
+    /// The current time, drawn with `colors`
+    pub fn new(colors: &'a ColorScheme) -> Self {
+        FloatingTime { colors, time: None }
+    }
+
+    /// Show this time, in seconds since midnight, instead of the current
+    /// local time
+    pub fn at(mut self, seconds: u32) -> Self {
+        self.time = Some(seconds);
+        self
+    }
+}
+
+impl Widget for FloatingTime<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width as usize;
+        if width == 0 {
+            return;
+        }
+
+        let seconds = self.time.unwrap_or_else(seconds_now);
+        let time_str = format_hm(seconds);
+        let time_pos = cell_at(seconds, width);
+
+        // Keep the time on screen at either end of the bar
+        let time_len = time_str.len();
+        let time_start = time_pos.saturating_sub(time_len / 2).min(width.saturating_sub(time_len));
+
+        let mut time_line = " ".repeat(width);
+        let end = (time_start + time_len).min(width);
+        time_line.replace_range(time_start..end, &time_str[..end - time_start]);
+
+        let mut pointer_line = " ".repeat(width);
+        pointer_line.replace_range(time_pos..time_pos + 1, "▼");
+
+        Paragraph::new(vec![Line::from(time_line), Line::from(pointer_line)])
+            .style(Style::default().fg(self.colors.foreground).add_modifier(Modifier::BOLD))
+            .render(area, buf);
+    }
+}
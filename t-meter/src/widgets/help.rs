@@ -0,0 +1,127 @@
+//! A framed list of keys and what they do.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+use crate::theme::ColorScheme;
+
+// Width of the key column, including the brackets
+const KEY_WIDTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Row<'a> {
+    Key(&'a str, &'a str),
+    Text(&'a str),
+    Gap,
+}
+
+/// A box listing keys and what they do, centred at the top of its area.
+/// The frame is drawn in the marker color and keys are highlighted.
+///
+/// ```
+/// use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+/// use t_meter::{theme, widgets::HelpBox};
+///
+/// let theme = theme::get_default_theme();
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 40, 8));
+/// HelpBox::new(&theme.light, "HELP")
+///     .key("q", "Quit")
+///     .gap()
+///     .text("Press [Esc] to close")
+///     .render(buffer.area, &mut buffer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HelpBox<'a> {
+    colors: &'a ColorScheme,
+    title: &'a str,
+    rows: Vec<Row<'a>>,
+}
+
+impl<'a> HelpBox<'a> {
+    /// An empty box with `title` in its top border
+    pub fn new(colors: &'a ColorScheme, title: &'a str) -> Self {
+        HelpBox { colors, title, rows: Vec::new() }
+    }
+
+    /// A row for `key`, shown in brackets, and what it does
+    pub fn key(mut self, key: &'a str, description: &'a str) -> Self {
+        self.rows.push(Row::Key(key, description));
+        self
+    }
+
+    /// A row of text. Anything in `[brackets]` is highlighted as a key.
+    pub fn text(mut self, text: &'a str) -> Self {
+        self.rows.push(Row::Text(text));
+        self
+    }
+
+    /// An empty row, to separate groups of keys
+    pub fn gap(mut self) -> Self {
+        self.rows.push(Row::Gap);
+        self
+    }
+
+    /// The contents of each row, without the frame
+    fn row_texts(&self) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| match row {
+                Row::Key(key, description) => {
+                    format!("  {:<width$}{}", format!("[{}]", key), description, width = KEY_WIDTH)
+                }
+                Row::Text(text) => format!("  {}", text),
+                Row::Gap => String::new(),
+            })
+            .collect()
+    }
+}
+
+impl Widget for HelpBox<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let frame_style = Style::default().fg(self.colors.marker);
+        let key_style = Style::default().fg(self.colors.highlight).add_modifier(Modifier::BOLD);
+
+        let texts = self.row_texts();
+        let content_width = texts.iter().map(|text| text.chars().count()).max().unwrap_or(0);
+        let inner = (content_width + 2).max(self.title.chars().count() + 8);
+
+        let title = format!(" {} ", self.title);
+        let left = (inner - title.chars().count()) / 2;
+        let right = inner - title.chars().count() - left;
+        let top = format!("┌{}{}{}┐", "─".repeat(left), title, "─".repeat(right));
+        let bottom = format!("└{}┘", "─".repeat(inner));
+
+        // A blank row inside the frame above and below the rows
+        let rows = [String::new()].into_iter().chain(texts).chain([String::new()]);
+        let mut lines = vec![Line::styled(top, frame_style)];
+        lines.extend(rows.map(|text| framed(text, inner, frame_style, key_style)));
+        lines.push(Line::styled(bottom, frame_style));
+
+        let width = (inner as u16 + 2).min(area.width);
+        let area = Rect { x: area.x + (area.width - width) / 2, width, ..area };
+        Paragraph::new(lines).style(Style::default().fg(self.colors.foreground)).render(area, buf);
+    }
+}
+
+/// `text` padded to `inner` cells between the sides of the frame, with
+/// `[keys]` highlighted
+fn framed(text: String, inner: usize, frame_style: Style, key_style: Style) -> Line<'static> {
+    let padding = inner.saturating_sub(text.chars().count());
+    let mut rest = text.as_str();
+
+    let mut spans = vec![Span::styled("│", frame_style)];
+    while let (Some(open), Some(close)) = (rest.find('['), rest.find(']')) {
+        spans.push(Span::raw(rest[..open].to_string()));
+        spans.push(Span::styled(rest[open..=close].to_string(), key_style));
+        rest = &rest[close + 1..];
+    }
+    spans.push(Span::raw(format!("{}{}", rest, " ".repeat(padding))));
+    spans.push(Span::styled("│", frame_style));
+
+    Line::from(spans)
+}
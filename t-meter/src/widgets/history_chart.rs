@@ -0,0 +1,203 @@
+//! Past days as rows of mini-bars, like a sleep tracker's chart.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::history::Day;
+use crate::schedule::{cell_at, SECONDS_PER_DAY};
+
+use super::day_bar::{cell_glyph, separator_cells, with_bar_background, DayBar};
+
+/// What a stretch of a past day is shown as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Nothing recorded
+    Unknown,
+    Awake,
+    Asleep,
+    Focus,
+}
+
+/// Past days as rows of mini-bars on the same 24 hour axis as a [`DayBar`],
+/// oldest at the top, each after its date. Time asleep is filled in with the
+/// bar's glyphs and gradient, time awake is the empty part of the bar, focus
+/// sessions are in the highlight color and the planned wake-up and bed times
+/// are markers. Hours nothing was recorded for are left blank.
+///
+/// The bar passed in only sets the look: its style, glyphs and colors.
+///
+/// ```
+/// use chrono::Local;
+/// use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+/// use t_meter::history::History;
+/// use t_meter::widgets::HistoryChart;
+/// use t_meter::{theme, DayBar, Schedule};
+///
+/// let theme = theme::get_default_theme();
+/// let mut history = History::new();
+/// history.mark_wake(Local::now(), Schedule::default());
+///
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 60, 7));
+/// HistoryChart::new(DayBar::new(&theme.dark), history.days(), Local::now().date_naive())
+///     .render(buffer.area, &mut buffer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HistoryChart<'a> {
+    bar: DayBar<'a>,
+    days: &'a [Day],
+    last: NaiveDate,
+    now: Option<NaiveDateTime>,
+}
+
+impl<'a> HistoryChart<'a> {
+    /// Width of the dates in front of the rows, including the space after
+    /// them
+    pub const LABEL_WIDTH: u16 = 7;
+
+    /// As many days as fit, up to and including `last`, from `days` as kept
+    /// by [`History`](crate::history::History)
+    pub fn new(bar: DayBar<'a>, days: &'a [Day], last: NaiveDate) -> Self {
+        HistoryChart { bar, days, last, now: None }
+    }
+
+    /// Leave the time after `now` blank and show the indicator there, for
+    /// charts that run up to today
+    pub fn now(mut self, now: DateTime<Local>) -> Self {
+        self.now = Some(now.naive_local());
+        self
+    }
+
+    fn day(&self, date: NaiveDate) -> Option<&'a Day> {
+        let index = self.days.binary_search_by_key(&date, |day| day.date).ok()?;
+        Some(&self.days[index])
+    }
+
+    /// What the moment `at` on `date` is shown as
+    fn phase(&self, date: NaiveDate, at: NaiveDateTime) -> Phase {
+        let day = self.day(date);
+        let within = |start: NaiveDateTime, end: Option<NaiveDateTime>| {
+            start <= at && end.or(self.now).is_none_or(|end| at < end)
+        };
+
+        let focus = day.iter().flat_map(|day| &day.focus).any(|session| {
+            within(session.start.naive_local(), session.end.map(|end| end.naive_local()))
+        });
+        if focus {
+            return Phase::Focus;
+        }
+
+        // The night that ends on this date and the one that starts on it
+        let nights = [date.pred_opt().and_then(|before| self.day(before)), day];
+        let asleep = nights.into_iter().flatten().any(|night| {
+            let Some(bed) = night.went_to_bed else {
+                return false;
+            };
+            let next = night.date.succ_opt().and_then(|after| self.day(after));
+            // Not knowing when it ended, up to the planned wake-up
+            let end = next.and_then(|next| next.woke_up.or(Some(next.first_opened)));
+            let planned = night.date.succ_opt().map(|after| at_seconds(after, night.wake));
+            within(bed.naive_local(), end.map(|end| end.naive_local()).or(planned))
+        });
+        if asleep {
+            return Phase::Asleep;
+        }
+
+        let awake = day.is_some_and(|day| {
+            let start = day.woke_up.unwrap_or(day.first_opened).naive_local();
+            let end = day.went_to_bed.unwrap_or(day.last_seen);
+            within(start, Some(end.naive_local()))
+        });
+        if awake {
+            Phase::Awake
+        } else {
+            Phase::Unknown
+        }
+    }
+
+    fn row(&self, date: NaiveDate, width: usize) -> Line<'static> {
+        let bar = &self.bar;
+        let colors = bar.colors;
+        let glyphs = bar.glyphs();
+        let gradient_mode = bar.gradient_mode.unwrap_or(colors.gradient_mode);
+        let separators = separator_cells(width);
+        let markers: Vec<usize> = self
+            .day(date)
+            .map(|day| vec![cell_at(day.wake, width), cell_at(day.bed, width)])
+            .unwrap_or_default();
+        let now_cell = self.now.filter(|now| now.date() == date).map(|now| {
+            cell_at(now.time().signed_duration_since(NaiveTime::MIN).num_seconds() as u32, width)
+        });
+
+        let spans: Vec<Span> = (0..width)
+            .map(|i| {
+                // The middle of the cell stands for all of it
+                let seconds = ((2 * i + 1) * SECONDS_PER_DAY as usize / (2 * width)) as u32;
+                let at = at_seconds(date, seconds);
+                if Some(i) == now_cell {
+                    let style =
+                        Style::default().fg(colors.progress_indicator).add_modifier(Modifier::BOLD);
+                    return Span::styled(glyphs.indicator.to_string(), style);
+                }
+                if self.now.is_some_and(|now| at > now) {
+                    return Span::raw(" ");
+                }
+                if markers.contains(&i) {
+                    let style = Style::default().fg(colors.marker).add_modifier(Modifier::BOLD);
+                    return Span::styled(glyphs.marker.to_string(), style);
+                }
+
+                let phase = self.phase(date, at);
+                if phase == Phase::Unknown {
+                    return Span::raw(" ");
+                }
+                if let Some(separator) = glyphs.separator.filter(|_| separators.contains(&i)) {
+                    return Span::raw(separator.to_string());
+                }
+                let (glyph, style) = match phase {
+                    Phase::Focus => (glyphs.filled, Style::default().fg(colors.highlight)),
+                    Phase::Asleep => cell_glyph(
+                        bar.style,
+                        glyphs,
+                        colors,
+                        1.0,
+                        gradient_mode.position(i, width, 1.0),
+                    ),
+                    _ => cell_glyph(bar.style, glyphs, colors, 0.0, 0.0),
+                };
+                Span::styled(glyph.to_string(), style)
+            })
+            .collect();
+
+        with_bar_background(Line::from(spans), colors)
+    }
+}
+
+/// `seconds` after midnight on `date`
+fn at_seconds(date: NaiveDate, seconds: u32) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN) + Duration::seconds(seconds.into())
+}
+
+impl Widget for HistoryChart<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        let width = area.width.saturating_sub(Self::LABEL_WIDTH) as usize;
+        if width < 2 {
+            return;
+        }
+
+        let label_style = Style::default().fg(self.bar.colors.marker_label);
+        let rows = area.height as i64;
+        for (row, y) in (area.top()..area.bottom()).enumerate() {
+            let date = self.last - Duration::days(rows - 1 - row as i64);
+            let label = Span::styled(date.format("%a %d ").to_string(), label_style);
+            buf.set_span(area.x, y, &label, Self::LABEL_WIDTH);
+            buf.set_line(area.x + Self::LABEL_WIDTH, y, &self.row(date, width), width as u16);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! How much of the day has gone and how much is left.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+use crate::schedule::{format_hm, SECONDS_PER_DAY};
+use crate::theme::ColorScheme;
+
+use super::seconds_now;
+
+/// Time elapsed and remaining in the day, centred. Stacked on two rows when
+/// there is room, side by side on one otherwise.
+#[derive(Debug, Clone)]
+pub struct Legend<'a> {
+    colors: &'a ColorScheme,
+    time: Option<u32>,
+}
+
+impl<'a> Legend<'a> {
+    /// The legend for the current time, drawn with `colors`
+    pub fn new(colors: &'a ColorScheme) -> Self {
+        Legend { colors, time: None }
+    }
+
+    /// Show this time, in seconds since midnight, instead of the current
+    /// local time. Times past the end of the day, from 86400 on, show the
+    /// whole day as elapsed.
+    pub fn at(mut self, seconds: u32) -> Self {
+        self.time = Some(seconds.min(SECONDS_PER_DAY));
+        self
+    }
+}
+
+impl Widget for Legend<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let colors = self.colors;
+        let elapsed = self.time.unwrap_or_else(seconds_now);
+        let entries = [
+            ("● Elapsed:   ", colors.legend_elapsed, elapsed),
+            ("○ Remaining: ", colors.legend_remaining, SECONDS_PER_DAY - elapsed),
+        ];
+        // Each entry in full, and with only its symbol when that is too wide
+        let entries = entries.map(|(label, color, seconds)| {
+            let entry = |label| {
+                Line::from(vec![
+                    Span::styled(label, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::raw(format_hm(seconds)),
+                ])
+            };
+            (entry(label), entry(&label[..label.find(' ').unwrap_or(0) + 1]))
+        });
+
+        // Whole entries only: those that don't fit are left out rather than
+        // cut off
+        let width = area.width as usize;
+        let fitting = |(full, short): (Line<'static>, Line<'static>)| {
+            if full.width() <= width {
+                full
+            } else {
+                short
+            }
+        };
+        let lines = if area.height >= 2 {
+            entries.map(fitting).to_vec()
+        } else {
+            let mut spans = Vec::new();
+            for (full, _) in entries.iter() {
+                let gap = if spans.is_empty() { 0 } else { 3 };
+                if Line::from(spans.clone()).width() + gap + full.width() > width {
+                    break;
+                }
+                if gap > 0 {
+                    spans.push(Span::raw("   "));
+                }
+                spans.extend(full.spans.iter().cloned());
+            }
+            if spans.is_empty() {
+                let [first, _] = entries;
+                spans = fitting(first).spans;
+            }
+            vec![Line::from(spans)]
+        };
+
+        let mut style = Style::default();
+        if let Some(background) = colors.legend_background {
+            style = style.bg(background);
+        }
+        Paragraph::new(lines).style(style).alignment(Alignment::Center).render(area, buf);
+    }
+}
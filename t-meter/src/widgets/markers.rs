@@ -0,0 +1,271 @@
+//! Times of day called out under the bar.
+
+use std::iter;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::schedule::{cell_at, format_hm};
+use crate::theme::ColorScheme;
+
+/// A time of day to call out, such as wake-up or bed time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    seconds: u32,
+    time: String,
+    label: String,
+    highlighted: bool,
+    secondary: bool,
+}
+
+impl Marker {
+    /// A marker at `seconds` after midnight, showing its time as `HH:MM`
+    pub fn new(seconds: u32, label: impl Into<String>) -> Self {
+        Marker {
+            seconds,
+            time: format_hm(seconds),
+            label: label.into(),
+            highlighted: false,
+            secondary: false,
+        }
+    }
+
+    /// Show this text instead of the time, such as a time being typed
+    pub fn time(mut self, text: impl Into<String>) -> Self {
+        self.time = text.into();
+        self
+    }
+
+    /// Draw the time in the highlight color, such as while it is edited
+    pub fn highlighted(mut self, highlighted: bool) -> Self {
+        self.highlighted = highlighted;
+        self
+    }
+
+    /// Draw the marker subdued, such as an actual time next to the planned
+    /// one. Secondary markers give way to the others, moving aside rather
+    /// than covering them.
+    pub fn secondary(mut self, secondary: bool) -> Self {
+        self.secondary = secondary;
+        self
+    }
+
+    /// Whether the marker is drawn subdued
+    pub fn is_secondary(&self) -> bool {
+        self.secondary
+    }
+
+    /// Where the marker is, in seconds since midnight
+    pub fn seconds(&self) -> u32 {
+        self.seconds
+    }
+
+    /// The time text shown for the marker
+    pub fn time_text(&self) -> &str {
+        &self.time
+    }
+
+    /// What the marker is
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Style of the time text
+    pub fn time_style(&self, colors: &ColorScheme) -> Style {
+        if self.highlighted {
+            Style::default().fg(colors.highlight).add_modifier(Modifier::BOLD)
+        } else if self.secondary {
+            Style::default().fg(colors.marker_label).add_modifier(Modifier::ITALIC)
+        } else {
+            Style::default().fg(colors.marker)
+        }
+    }
+}
+
+/// Markers lined up under a bar of the same width: a row of ticks, a row of
+/// times and a row of labels, each centred on its marker. Rows that are
+/// turned off are skipped, and rows that don't fit are cut off at the
+/// bottom.
+#[derive(Debug, Clone)]
+pub struct Markers<'a> {
+    colors: &'a ColorScheme,
+    markers: Vec<Marker>,
+    ticks: bool,
+    labels: bool,
+}
+
+impl<'a> Markers<'a> {
+    /// All three rows for `markers`, drawn with `colors`
+    pub fn new(colors: &'a ColorScheme, markers: impl IntoIterator<Item = Marker>) -> Self {
+        Markers { colors, markers: markers.into_iter().collect(), ticks: true, labels: true }
+    }
+
+    /// Whether to draw the row of ticks
+    pub fn ticks(mut self, ticks: bool) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// Whether to draw the row of labels
+    pub fn labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Where each marker's time and label are centred. Markers are placed
+    /// highlighted first, then in order, and those that would run into one
+    /// already placed are left out. Secondary markers go last and move to
+    /// the nearest free spot on the side of the time they show, then on the
+    /// other side, before they are left out.
+    fn positions(&self, width: usize) -> Vec<Option<usize>> {
+        let mut taken = vec![false; width];
+        let mut positions = vec![None; self.markers.len()];
+        let mut primary: Vec<usize> =
+            (0..self.markers.len()).filter(|&i| !self.markers[i].secondary).collect();
+        primary.sort_by_key(|&i| !self.markers[i].highlighted);
+        for i in primary {
+            let pos = cell_at(self.markers[i].seconds, width);
+            if self.fits(&self.markers[i], pos, width, &taken) {
+                self.take(&self.markers[i], pos, width, &mut taken);
+                positions[i] = Some(pos);
+            }
+        }
+
+        for (i, marker) in self.markers.iter().enumerate().filter(|(_, m)| m.secondary) {
+            let later = self
+                .markers
+                .iter()
+                .filter(|other| !other.secondary)
+                .min_by_key(|other| other.seconds.abs_diff(marker.seconds))
+                .is_some_and(|other| marker.seconds >= other.seconds);
+
+            let pos = cell_at(marker.seconds, width);
+            let after = (pos..width).find(|&p| self.fits(marker, p, width, &taken));
+            let before = (0..pos).rev().find(|&p| self.fits(marker, p, width, &taken));
+            let found = if later { after.or(before) } else { before.or(after) };
+            if let Some(found) = found {
+                self.take(marker, found, width, &mut taken);
+                positions[i] = Some(found);
+            }
+        }
+        positions
+    }
+
+    /// Whether every character of `marker` shows when centred on `pos`,
+    /// with a free cell either side to keep it apart from its neighbours
+    fn fits(&self, marker: &Marker, pos: usize, width: usize, taken: &[bool]) -> bool {
+        self.texts(marker).all(|text| {
+            let cells: Vec<usize> = centred(text, pos, width).map(|(i, _)| i).collect();
+            match (cells.first(), cells.last()) {
+                (Some(&first), Some(&last)) => {
+                    cells.len() == text.chars().count()
+                        && (first.saturating_sub(1)..=(last + 1).min(width - 1))
+                            .all(|cell| !taken[cell])
+                }
+                _ => true,
+            }
+        })
+    }
+
+    fn take(&self, marker: &Marker, pos: usize, width: usize, taken: &mut [bool]) {
+        for (cell, _) in self.texts(marker).flat_map(|text| centred(text, pos, width)) {
+            taken[cell] = true;
+        }
+    }
+
+    /// The texts drawn for `marker`: its time, and its label when labels
+    /// are shown
+    fn texts<'m>(&self, marker: &'m Marker) -> impl Iterator<Item = &'m str> {
+        let label = self.labels.then_some(marker.label.as_str());
+        iter::once(marker.time.as_str()).chain(label)
+    }
+
+    /// Secondary ticks go down first, so the others draw over them
+    fn ticks_line(&self, width: usize) -> Line<'static> {
+        let mut ticks = vec![' '; width];
+        for marker in self.markers.iter().filter(|m| m.secondary) {
+            ticks[cell_at(marker.seconds, width)] = '┊';
+        }
+        for marker in self.markers.iter().filter(|m| !m.secondary) {
+            ticks[cell_at(marker.seconds, width)] = '│';
+        }
+        Line::styled(ticks.into_iter().collect::<String>(), Style::default().fg(self.colors.marker))
+    }
+
+    /// Times need their own style per marker so a highlighted one stands
+    /// out, so every cell keeps a style alongside its character
+    fn times_line(&self, width: usize, positions: &[Option<usize>]) -> Line<'static> {
+        let mut chars = vec![' '; width];
+        let mut styles = vec![Style::default().fg(self.colors.marker); width];
+        for (marker, pos) in self.markers.iter().zip(positions) {
+            let Some(pos) = *pos else { continue };
+            let style = marker.time_style(self.colors);
+            for (i, c) in centred(&marker.time, pos, width) {
+                chars[i] = c;
+                styles[i] = style;
+            }
+        }
+
+        let mut spans = Vec::new();
+        let mut current_style = styles[0];
+        let mut current_text = String::new();
+        for (c, style) in chars.into_iter().zip(styles) {
+            if style != current_style {
+                spans.push(Span::styled(std::mem::take(&mut current_text), current_style));
+                current_style = style;
+            }
+            current_text.push(c);
+        }
+        spans.push(Span::styled(current_text, current_style));
+        Line::from(spans)
+    }
+
+    fn labels_line(&self, width: usize, positions: &[Option<usize>]) -> Line<'static> {
+        let mut labels = vec![' '; width];
+        for (marker, pos) in self.markers.iter().zip(positions) {
+            let Some(pos) = *pos else { continue };
+            for (i, c) in centred(&marker.label, pos, width) {
+                labels[i] = c;
+            }
+        }
+        Line::styled(
+            labels.into_iter().collect::<String>(),
+            Style::default().fg(self.colors.marker_label),
+        )
+    }
+}
+
+/// The cells and characters of `text` centred on `pos`, kept within `width`
+fn centred(text: &str, pos: usize, width: usize) -> impl Iterator<Item = (usize, char)> + '_ {
+    let len = text.chars().count();
+    let start = pos.saturating_sub(len / 2).min(width.saturating_sub(len));
+    text.chars().enumerate().map(move |(i, c)| (start + i, c)).filter(move |(i, _)| *i < width)
+}
+
+impl Widget for Markers<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = area.width as usize;
+        if width == 0 {
+            return;
+        }
+
+        let positions = self.positions(width);
+        let mut lines = Vec::new();
+        if self.ticks {
+            lines.push(self.ticks_line(width));
+        }
+        lines.push(self.times_line(width, &positions));
+        if self.labels {
+            lines.push(self.labels_line(width, &positions));
+        }
+
+        for (y, line) in (area.top()..area.bottom()).zip(&lines) {
+            buf.set_line(area.x, y, line, area.width);
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Ratatui widgets for drawing the day: the bar itself and the pieces of
+//! the t-meter screen around it. Widgets that show a time take it from
+//! `at`, or use the current local time.
+
+use chrono::{Local, Timelike};
+
+mod day_bar;
+mod floating_time;
+mod help;
+mod history_chart;
+mod legend;
+mod markers;
+mod quote;
+
+pub use day_bar::{DayBar, DayBarState};
+pub use floating_time::FloatingTime;
+pub use help::HelpBox;
+pub use history_chart::HistoryChart;
+pub use legend::Legend;
+pub use markers::{Marker, Markers};
+pub use quote::QuoteBlock;
+
+/// Seconds since midnight, local time
+fn seconds_now() -> u32 {
+    Local::now().num_seconds_from_midnight()
+}
@@ -0,0 +1,41 @@
+//! A quote and its author.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{Paragraph, Widget, Wrap},
+};
+
+use crate::quotes::Quote;
+use crate::theme::ColorScheme;
+
+/// A quote in quotation marks with its author underneath, centred and
+/// wrapped to the width of the area
+#[derive(Debug, Clone)]
+pub struct QuoteBlock<'a> {
+    colors: &'a ColorScheme,
+    quote: Quote,
+}
+
+impl<'a> QuoteBlock<'a> {
+    /// `quote`, drawn with `colors`
+    pub fn new(colors: &'a ColorScheme, quote: Quote) -> Self {
+        QuoteBlock { colors, quote }
+    }
+}
+
+impl Widget for QuoteBlock<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut style = Style::default().add_modifier(Modifier::ITALIC).fg(self.colors.quote);
+        if let Some(background) = self.colors.quote_background {
+            style = style.bg(background);
+        }
+
+        Paragraph::new(format!("\"{}\"\n~ {}", self.quote.text, self.quote.author))
+            .style(style)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,16 @@
+//! Command-line arguments that are refused before anything runs.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str::contains;
+
+#[test]
+fn refuses_speeds_that_would_stop_or_break_the_clock() {
+    for speed in ["0", "-1", "NaN", "inf", "1e300", "fast"] {
+        cargo_bin_cmd!("t-meter")
+            .arg(format!("--speed={}", speed))
+            .args(["--at", "12:00"])
+            .assert()
+            .failure()
+            .stderr(contains("Invalid speed"));
+    }
+}
@@ -0,0 +1,34 @@
+//! Fixtures shared by the integration tests: times on fixed days, and
+//! `t-meter` run in a temporary home.
+
+// Each test crate compiles its own copy and uses only some of these
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+
+/// `hour:minute` on October `day`, 2026
+pub fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap()
+}
+
+/// October `day`, 2026
+pub fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+/// `t-meter` with `home` as its home and every XDG directory inside it, so
+/// nothing outside is read or written
+pub fn t_meter(home: &Path) -> assert_cmd::Command {
+    let mut command = cargo_bin_cmd!("t-meter");
+    command
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_DATA_HOME", home.join(".local/share"))
+        .env("XDG_STATE_HOME", home.join(".local/state"))
+        .env_remove("T_METER_LOG")
+        .current_dir(home);
+    command
+}
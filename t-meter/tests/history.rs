@@ -0,0 +1,172 @@
+//! The history file format: recording days and focus sessions, and reading
+//! back what was written.
+
+use std::io::{self, Cursor};
+
+use chrono::{Duration, NaiveDate};
+use t_meter::history::{self, History, SCHEMA_VERSION};
+use t_meter::Schedule;
+
+mod common;
+use common::{at, date};
+
+fn written(history: &History) -> String {
+    let mut out = Vec::new();
+    history.write(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn records_a_day_and_reads_it_back() {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    let mut history = History::new();
+    history.record(at(17, 8, 2), schedule);
+    history.record(at(17, 22, 41), Schedule::parse("06:30", "22:30").unwrap());
+
+    let day = history.day(date(17)).unwrap();
+    assert_eq!(day.first_opened, at(17, 8, 2));
+    assert_eq!(day.last_seen, at(17, 22, 41));
+    assert_eq!(day.schedule(), Schedule::parse("06:30", "22:30").unwrap());
+
+    let text = written(&history);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], format!("{{\"schema_version\":{}}}", SCHEMA_VERSION));
+    assert!(lines[1].starts_with("{\"date\":\"2026-10-17\",\"wake\":\"06:30\",\"bed\":\"22:30\","));
+    assert_eq!(History::read(Cursor::new(text)).unwrap(), history);
+}
+
+#[test]
+fn keeps_days_in_order() {
+    let schedule = Schedule::default();
+    let mut history = History::new();
+    for day in [18, 16, 17, 16] {
+        history.record(at(day, 9, 0), schedule);
+    }
+
+    let dates: Vec<NaiveDate> = history.days().iter().map(|day| day.date).collect();
+    assert_eq!(dates, [date(16), date(17), date(18)]);
+}
+
+#[test]
+fn focus_sessions() {
+    let schedule = Schedule::default();
+    let mut history = History::new();
+    history.start_focus(at(17, 9, 0), schedule);
+    // Already running
+    history.start_focus(at(17, 9, 10), schedule);
+    assert_eq!(history.running_focus().unwrap().start, at(17, 9, 0));
+
+    let session = history.end_focus(at(17, 9, 50)).unwrap();
+    assert_eq!(session.duration(), Some(Duration::minutes(50)));
+    assert!(history.running_focus().is_none());
+    assert!(history.end_focus(at(17, 10, 0)).is_none());
+
+    // Left running by a t-meter that went away at 11:30
+    history.start_focus(at(17, 11, 0), schedule);
+    history.record(at(17, 11, 30), schedule);
+    history.close_abandoned_focus();
+    assert_eq!(history.day(date(17)).unwrap().focus_time(), Duration::minutes(80));
+}
+
+#[test]
+fn reads_days_one_at_a_time() {
+    let text = "{\"schema_version\":1}\n\n\
+        {\"date\":\"2026-10-16\",\"wake\":\"07:00\",\"bed\":\"23:00\",\
+        \"first_opened\":\"2026-10-16T08:00:00Z\",\"last_seen\":\"2026-10-16T20:00:00Z\",\
+        \"mood\":\"fine\"}\n";
+
+    let days: Vec<_> =
+        history::read_days(Cursor::new(text)).unwrap().collect::<io::Result<_>>().unwrap();
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].date, date(16));
+    assert!(days[0].focus.is_empty());
+
+    assert_eq!(History::read(Cursor::new("")).unwrap(), History::new());
+}
+
+#[test]
+fn refuses_newer_and_broken_files() {
+    let newer = format!("{{\"schema_version\":{}}}\n", SCHEMA_VERSION + 1);
+    let err = History::read(Cursor::new(newer)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("newer"), "{}", err);
+
+    let broken = "{\"schema_version\":1}\n{\"date\":\"yesterday\"}\n";
+    assert_eq!(History::read(Cursor::new(broken)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn marks_getting_up_and_going_to_bed() {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    let mut history = History::new();
+    history.mark_wake(at(16, 7, 20), schedule);
+    history.mark_bed(at(16, 23, 10), schedule);
+    // Past midnight, so still the 16th
+    history.mark_bed(at(17, 0, 35), schedule);
+    history.mark_wake(at(17, 6, 50), schedule);
+
+    let day = history.day(date(16)).unwrap();
+    assert_eq!(day.woke_up, Some(at(16, 7, 20)));
+    assert_eq!(day.went_to_bed, Some(at(17, 0, 35)));
+    let day = history.day(date(17)).unwrap();
+    assert_eq!(day.woke_up, Some(at(17, 6, 50)));
+    assert_eq!(day.went_to_bed, None);
+
+    assert_eq!(History::read(Cursor::new(written(&history))).unwrap(), history);
+    assert_eq!(history::waking_day(at(17, 6, 59), schedule), date(16));
+    assert_eq!(history::waking_day(at(17, 7, 0), schedule), date(17));
+}
+
+#[test]
+fn shows_getting_up_before_the_plan_right_away() {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    let mut history = History::new();
+    history.mark_bed(at(17, 0, 20), schedule);
+    history.mark_wake(at(17, 6, 30), schedule);
+
+    assert_eq!(history.day(date(17)).unwrap().woke_up, Some(at(17, 6, 30)));
+    // Before the planned wake-up the night before is still in view
+    assert_eq!(history.marks(at(17, 6, 31), schedule), (Some(at(17, 6, 30)), Some(at(17, 0, 20))));
+    assert_eq!(history.marks(at(17, 7, 0), schedule), (Some(at(17, 6, 30)), None));
+    assert_eq!(history.marks(at(18, 6, 0), schedule), (None, None));
+}
+
+#[test]
+fn merges_what_another_writer_saved() {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    let mut file = History::new();
+    file.mark_wake(at(17, 7, 0), schedule);
+
+    // Two t-meters open on the same file
+    let (base_a, base_b) = (file.clone(), file.clone());
+    let (mut a, mut b) = (file.clone(), file.clone());
+    a.mark_wake(at(17, 7, 20), schedule);
+    a.start_focus(at(17, 9, 0), schedule);
+    b.mark_bed(at(17, 23, 15), schedule);
+    b.record(at(17, 23, 15), schedule);
+
+    // A saves, then B saves on top of it
+    a.merge(&base_a, &file);
+    file = a.clone();
+    b.merge(&base_b, &file);
+    file = b.clone();
+
+    let day = file.day(date(17)).unwrap();
+    assert_eq!(day.woke_up, Some(at(17, 7, 20)));
+    assert_eq!(day.went_to_bed, Some(at(17, 23, 15)));
+    assert_eq!(day.last_seen, at(17, 23, 15));
+    assert_eq!(file.running_focus().unwrap().start, at(17, 9, 0));
+
+    // A ends its session and picks up B's bed time
+    let base_a = a.clone();
+    a.end_focus(at(17, 9, 45));
+    a.merge(&base_a, &file);
+    let day = a.day(date(17)).unwrap();
+    assert_eq!(day.focus_time(), Duration::minutes(45));
+    assert_eq!(day.went_to_bed, Some(at(17, 23, 15)));
+
+    // Days only one side has are kept
+    b.record(at(18, 8, 0), schedule);
+    a.merge(&file, &b);
+    assert_eq!(a.days().len(), 2);
+}
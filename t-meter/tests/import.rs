@@ -0,0 +1,111 @@
+//! `t-meter theme import`: each supported format read from the files in
+//! `tests/fixtures`, and theme names that can't be used as file names.
+
+use std::path::{Path, PathBuf};
+
+use predicates::str::contains;
+use ratatui::style::Color;
+use t_meter::Theme;
+
+mod common;
+
+const BACKGROUND: Color = Color::Rgb(0x1d, 0x1f, 0x21);
+const FOREGROUND: Color = Color::Rgb(0xc5, 0xc8, 0xc6);
+const BLUE: Color = Color::Rgb(0x81, 0xa2, 0xbe);
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+/// Run `t-meter theme import` with `home` as the home directory
+fn import(home: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    common::t_meter(home).args(["theme", "import"]).args(args).assert()
+}
+
+fn themes_dir(home: &Path) -> PathBuf {
+    home.join(".config/t-meter/themes")
+}
+
+/// The theme imported as `name`
+fn imported(home: &Path, name: &str) -> Theme {
+    let path = themes_dir(home).join(format!("{}.toml", name));
+    toml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Tomorrow Night, as every fixture holds it
+fn assert_tomorrow_night(theme: &Theme) {
+    assert_eq!(theme.dark.background, Some(BACKGROUND));
+    assert_eq!(theme.dark.foreground, FOREGROUND);
+    assert_eq!(theme.dark.title, BLUE);
+    assert_eq!(theme.light.background, Some(Color::Rgb(0xff, 0xff, 0xff)));
+    assert_eq!(theme.light.foreground, BACKGROUND);
+}
+
+#[test]
+fn imports_base16() {
+    let home = tempfile::tempdir().unwrap();
+    let file = fixture("tomorrow-night.yaml");
+    import(home.path(), &[file.to_str().unwrap()])
+        .success()
+        .stdout(contains("Imported theme 'tomorrow-night'"));
+
+    assert_tomorrow_night(&imported(home.path(), "tomorrow-night"));
+}
+
+#[test]
+fn imports_alacritty() {
+    let home = tempfile::tempdir().unwrap();
+    let file = fixture("tomorrow-night.toml");
+    import(home.path(), &[file.to_str().unwrap(), "--name", "Alacritty Night"]).success();
+
+    assert_tomorrow_night(&imported(home.path(), "alacritty-night"));
+}
+
+#[test]
+fn imports_iterm() {
+    let home = tempfile::tempdir().unwrap();
+    let file = fixture("Tomorrow Night.itermcolors");
+    import(home.path(), &[file.to_str().unwrap()]).success();
+
+    assert_tomorrow_night(&imported(home.path(), "tomorrow-night"));
+}
+
+#[test]
+fn refuses_to_overwrite_without_force() {
+    let home = tempfile::tempdir().unwrap();
+    let file = fixture("tomorrow-night.yaml");
+    import(home.path(), &[file.to_str().unwrap()]).success();
+    import(home.path(), &[file.to_str().unwrap()]).failure().stderr(contains("--force"));
+    import(home.path(), &[file.to_str().unwrap(), "--force"]).success();
+}
+
+#[test]
+fn keeps_names_inside_the_themes_directory() {
+    let home = tempfile::tempdir().unwrap();
+    let file = fixture("tomorrow-night.yaml");
+    import(home.path(), &[file.to_str().unwrap(), "--name", "../../escaped"])
+        .success()
+        .stdout(contains("Imported theme 'escaped'"));
+
+    assert!(themes_dir(home.path()).join("escaped.toml").exists());
+    assert!(!home.path().join("escaped.toml").exists());
+    assert!(!home.path().join(".config/escaped.toml").exists());
+}
+
+#[test]
+fn refuses_names_without_letters_or_digits() {
+    let home = tempfile::tempdir().unwrap();
+    let file = fixture("tomorrow-night.yaml");
+    import(home.path(), &[file.to_str().unwrap(), "--name", "日本"])
+        .failure()
+        .stderr(contains("pick one with --name"));
+
+    // A file whose name has nothing to make a theme name of
+    let unnamed = home.path().join("日本.yaml");
+    std::fs::copy(&file, &unnamed).unwrap();
+    import(home.path(), &[unnamed.to_str().unwrap()])
+        .failure()
+        .stderr(contains("pick one with --name"));
+
+    assert!(!themes_dir(home.path()).join(".toml").exists());
+}
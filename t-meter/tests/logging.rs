@@ -0,0 +1,78 @@
+//! Warnings going to the log file in the state directory, and to stderr
+//! outside the interactive view.
+
+use std::fs;
+use std::path::Path;
+
+use predicates::prelude::*;
+
+mod common;
+
+/// A temporary home with a theme file that doesn't parse
+fn home_with_broken_theme() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    let themes = home.path().join(".config/t-meter/themes");
+    fs::create_dir_all(&themes).unwrap();
+    fs::write(themes.join("broken.toml"), "name = [").unwrap();
+    home
+}
+
+/// A small snapshot, which loads the config and themes like the app does
+fn snapshot(home: &Path) -> assert_cmd::Command {
+    let mut command = common::t_meter(home);
+    command.args(["snapshot", "--width", "40", "--height", "4"]);
+    command
+}
+
+fn log_file(home: &Path) -> String {
+    fs::read_to_string(home.join(".local/state/t-meter/t-meter.log")).unwrap_or_default()
+}
+
+#[test]
+fn warnings_go_to_stderr_and_the_log() {
+    let home = home_with_broken_theme();
+    snapshot(home.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: Failed to load theme from"));
+
+    let log = log_file(home.path());
+    assert!(log.contains("WARN  t_meter::user_themes: Failed to load theme from"), "{}", log);
+    assert!(log.contains("INFO  t_meter::config: Loaded config from"), "{}", log);
+}
+
+#[test]
+fn leaves_out_reminders_more_than_a_day_early() {
+    let home = tempfile::tempdir().unwrap();
+    let config = home.path().join(".config/t-meter");
+    fs::create_dir_all(&config).unwrap();
+    let reminders = "[[reminders]]\nat = \"bed\"\nbefore = 4000000000\n";
+    fs::write(config.join("config.toml"), reminders).unwrap();
+
+    snapshot(home.path()).assert().success().stderr(predicate::str::contains(
+        "Warning: Reminder 'Bed time in 4000000000 min' is more than 1440 minutes early",
+    ));
+}
+
+#[test]
+fn level_comes_from_the_flag_or_the_environment() {
+    let home = home_with_broken_theme();
+    snapshot(home.path()).arg("--log-level").arg("error").assert().success().stderr("");
+    assert_eq!(log_file(home.path()), "");
+
+    snapshot(home.path()).env("T_METER_LOG", "warn").assert().success();
+    let log = log_file(home.path());
+    assert!(log.contains("WARN"), "{}", log);
+    assert!(!log.contains("INFO"), "{}", log);
+}
+
+#[test]
+fn rejects_unknown_levels() {
+    let home = tempfile::tempdir().unwrap();
+    snapshot(home.path())
+        .arg("--log-level")
+        .arg("loud")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid log level 'loud'"));
+}
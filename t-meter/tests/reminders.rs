@@ -0,0 +1,111 @@
+//! Reminders: reading them from the config, when they go off and that
+//! nothing from before t-meter started is replayed.
+
+use chrono::Duration;
+use t_meter::reminder::{Delivery, Reminder, Reminders, Target};
+use t_meter::Schedule;
+
+mod common;
+use common::at;
+
+fn schedule() -> Schedule {
+    Schedule::parse("07:00", "23:00").unwrap()
+}
+
+#[derive(serde::Deserialize)]
+struct Config {
+    reminders: Vec<Reminder>,
+}
+
+#[test]
+fn reads_reminders_from_toml() {
+    let config: Config = toml::from_str(
+        r#"
+        [[reminders]]
+        at = "wake"
+
+        [[reminders]]
+        at = "12:30"
+        before = 5
+        message = "Lunch"
+        delivery = ["bell", "osc777", "command"]
+        command = "notify-send t-meter"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.reminders[0], Reminder::new(Target::Wake));
+    let lunch = &config.reminders[1];
+    assert_eq!(lunch.at, Target::Time(12 * 3600 + 30 * 60));
+    assert_eq!(lunch.delivery, [Delivery::Bell, Delivery::Osc777, Delivery::Command]);
+    assert_eq!(lunch.command.as_deref(), Some("notify-send t-meter"));
+    assert_eq!(lunch.text(), "Lunch");
+
+    let err = toml::from_str::<Config>("[[reminders]]\nat = \"dinner\"").err().unwrap();
+    assert!(err.to_string().contains("'dinner' is not wake, noon, bed or a time"), "{}", err);
+}
+
+#[test]
+fn goes_off_before_its_target() {
+    let reminder = Reminder::new(Target::Wake).before(30);
+    assert_eq!(reminder.seconds(schedule()), 6 * 3600 + 30 * 60);
+    assert_eq!(reminder.text(), "Wake up in 30 min");
+
+    // Wrapping back past midnight
+    let early = Schedule::parse("00:10", "23:00").unwrap();
+    assert_eq!(reminder.seconds(early), 23 * 3600 + 40 * 60);
+}
+
+#[test]
+fn whole_days_before_make_no_difference() {
+    let day = Reminder::new(Target::Wake).before(24 * 60 + 30);
+    assert_eq!(day.seconds(schedule()), 6 * 3600 + 30 * 60);
+    // u32::MAX minutes is 255 minutes past a whole number of days
+    let forever = Reminder::new(Target::Wake).before(u32::MAX);
+    assert_eq!(forever.seconds(schedule()), 2 * 3600 + 45 * 60);
+}
+
+#[test]
+fn goes_off_once_and_not_for_times_before_the_start() {
+    let rules = [Reminder::new(Target::Bed).before(30), Reminder::new(Target::Noon)];
+    // Started after noon, which isn't caught up on
+    let mut reminders = Reminders::new(at(17, 12, 5));
+
+    assert!(reminders.due(&rules, schedule(), at(17, 22, 0)).is_empty());
+    assert_eq!(reminders.next(&rules, schedule(), at(17, 22, 0)), Some(at(17, 22, 30)));
+    assert_eq!(reminders.due(&rules, schedule(), at(17, 22, 31)), [rules[0].clone()]);
+    assert!(reminders.due(&rules, schedule(), at(17, 22, 40)).is_empty());
+
+    // Noon the next day
+    assert_eq!(reminders.next(&rules, schedule(), at(17, 22, 40)), Some(at(18, 12, 0)));
+    assert_eq!(reminders.due(&rules, schedule(), at(18, 12, 0)), [rules[1].clone()]);
+}
+
+#[test]
+fn skips_reminders_missed_by_more_than_the_grace() {
+    let rules = [Reminder::new(Target::Noon)];
+    let mut reminders = Reminders::new(at(17, 11, 0));
+
+    // Asleep from 11:00 to 12:30
+    assert!(reminders.due(&rules, schedule(), at(17, 12, 30)).is_empty());
+
+    let mut reminders = Reminders::new(at(17, 11, 0));
+    assert_eq!(reminders.due(&rules, schedule(), at(17, 12, 5)).len(), 1);
+}
+
+#[test]
+fn snoozed_reminders_come_back() {
+    let reminder = Reminder::new(Target::Noon).message("Lunch");
+    let mut reminders = Reminders::new(at(17, 12, 0));
+    reminders.snooze(reminder.clone(), at(17, 12, 10));
+
+    assert_eq!(reminders.next(&[], schedule(), at(17, 12, 1)), Some(at(17, 12, 10)));
+    assert!(reminders.due(&[], schedule(), at(17, 12, 9)).is_empty());
+    assert_eq!(reminders.due(&[], schedule(), at(17, 12, 10)), [reminder]);
+    assert!(reminders.due(&[], schedule(), at(17, 12, 20)).is_empty());
+    assert_eq!(reminders.next(&[], schedule(), at(17, 12, 20)), None);
+
+    // Grace applies to snoozes too
+    reminders.snooze(Reminder::new(Target::Noon), at(17, 12, 30));
+    assert!(reminders.due(&[], schedule(), at(17, 13, 0) + Duration::seconds(1)).is_empty());
+}
@@ -0,0 +1,153 @@
+//! `t-meter report` over a history in a temporary home. The column names
+//! and summary fields are what spreadsheets and scripts rely on, so they're
+//! checked exactly.
+
+use std::fs::{self, File};
+use std::path::Path;
+
+use predicates::prelude::*;
+use t_meter::history::History;
+use t_meter::Schedule;
+
+mod common;
+use common::at;
+
+const COLUMNS: &str = "date,planned_wake,planned_bed,woke_up,went_to_bed,wake_drift_minutes,\
+    bed_drift_minutes,slept_minutes,focus_minutes,focus_sessions,first_opened,last_seen";
+
+/// A home with three days of history: the 14th with no marks, then a night
+/// from the 15th to the 16th and a focus session on the 16th
+fn home_with_history() -> tempfile::TempDir {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    let mut history = History::new();
+    history.record(at(14, 9, 0), schedule);
+    history.mark_wake(at(15, 7, 10), schedule);
+    history.mark_bed(at(16, 0, 30), schedule);
+    history.mark_wake(at(16, 6, 45), schedule);
+    history.start_focus(at(16, 9, 0), schedule);
+    history.end_focus(at(16, 9, 40));
+    history.record(at(16, 21, 0), schedule);
+
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join(".local/share/t-meter");
+    fs::create_dir_all(&dir).unwrap();
+    history.write(File::create(dir.join("history.jsonl")).unwrap()).unwrap();
+    home
+}
+
+fn report(home: &Path, args: &[&str]) -> assert_cmd::assert::Assert {
+    common::t_meter(home).arg("report").args(args).assert()
+}
+
+fn stdout(assert: assert_cmd::assert::Assert) -> String {
+    String::from_utf8(assert.success().get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn csv_has_a_row_per_day() {
+    let home = home_with_history();
+    let csv = stdout(report(home.path(), &[]));
+
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        [
+            COLUMNS,
+            "2026-10-14,07:00,23:00,,,,,,0,0,09:00,09:00",
+            "2026-10-15,07:00,23:00,07:10,00:30,10,90,,0,0,07:10,07:10",
+            "2026-10-16,07:00,23:00,06:45,,-15,,375,40,1,00:30,21:00",
+        ]
+    );
+}
+
+#[test]
+fn days_can_be_picked_by_date() {
+    let home = home_with_history();
+    let csv = stdout(report(home.path(), &["--from", "2026-10-15", "--to", "2026-10-15"]));
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        [COLUMNS, "2026-10-15,07:00,23:00,07:10,00:30,10,90,,0,0,07:10,07:10"]
+    );
+
+    // The night before the first day still counts towards it
+    let csv = stdout(report(home.path(), &["--from", "2026-10-16"]));
+    assert!(csv.ends_with(",375,40,1,00:30,21:00\n"), "{}", csv);
+
+    report(home.path(), &["--from", "2026-10-16", "--to", "2026-10-15"])
+        .failure()
+        .stderr(predicate::str::contains("--from 2026-10-16 is after --to 2026-10-15"));
+    report(home.path(), &["--from", "yesterday"])
+        .failure()
+        .stderr(predicate::str::contains("Invalid date 'yesterday'"));
+}
+
+#[test]
+fn json_has_the_days_and_a_summary() {
+    let home = home_with_history();
+    let json = stdout(report(home.path(), &["--format", "json", "--to", "2026-10-31"]));
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(report["schema_version"], 1);
+    assert_eq!(report["from"], serde_json::Value::Null);
+    assert_eq!(report["to"], "2026-10-31");
+
+    let days = report["days"].as_array().unwrap();
+    assert_eq!(days.len(), 3);
+    let mut keys: Vec<&str> = days[2].as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort();
+    let mut columns: Vec<&str> = COLUMNS.split(',').collect();
+    columns.sort();
+    assert_eq!(keys, columns);
+    assert_eq!(days[0]["woke_up"], serde_json::Value::Null);
+    assert_eq!(days[2]["wake_drift_minutes"], -15);
+    assert_eq!(days[2]["slept_minutes"], 375);
+
+    assert_eq!(
+        report["summary"],
+        serde_json::json!({
+            "days": 3,
+            "nights": 1,
+            "average_slept_minutes": 375,
+            "wake": {"days": 2, "on_time": 2, "average_drift_minutes": -3, "spread_minutes": 13},
+            "bed": {"days": 1, "on_time": 0, "average_drift_minutes": 90, "spread_minutes": 0},
+            "focus_minutes": 40,
+            "focus_sessions": 1,
+        })
+    );
+}
+
+#[test]
+fn markdown_has_a_table_and_a_summary() {
+    let home = home_with_history();
+    let markdown = stdout(report(home.path(), &["--format", "markdown", "--from", "2026-10-15"]));
+
+    assert!(markdown
+        .starts_with("# t-meter report\n\nFrom 2026-10-15 to the end.\n\n| Date | Plan up |"));
+    assert!(
+        markdown.contains("| 2026-10-16 | 07:00 | 23:00 | 06:45 |  | -0:15 |  | 6:15 | 0:40 | 1 |")
+    );
+    assert!(markdown.contains("-   **Slept**: 6:15 on average over 1 night\n"), "{}", markdown);
+    assert!(markdown.contains("-   **Focus**: 0:40 in 1 session\n"), "{}", markdown);
+}
+
+#[test]
+fn an_empty_history_has_just_the_headings() {
+    let home = tempfile::tempdir().unwrap();
+    assert_eq!(stdout(report(home.path(), &[])), format!("{}\n", COLUMNS));
+
+    let json = stdout(report(home.path(), &["--format", "json"]));
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(report["days"], serde_json::json!([]));
+    assert_eq!(report["summary"]["wake"], serde_json::Value::Null);
+}
+
+#[test]
+fn refuses_a_newer_history() {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join(".local/share/t-meter");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("history.jsonl"), "{\"schema_version\":99}\n").unwrap();
+
+    report(home.path(), &[])
+        .failure()
+        .stderr(predicate::str::contains("newer than the 1 this build reads"));
+}
@@ -0,0 +1,89 @@
+//! When the redraw loop wakes up, driven by a clock moved on by hand.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use t_meter::clock::{Clock, FixedClock, ManualClock, SimulatedClock};
+use t_meter::scheduler::{Scheduler, MIN_INTERVAL};
+
+fn at(hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 6, 1, hour, minute, second).unwrap()
+}
+
+#[test]
+fn waits_for_the_next_minute() {
+    let scheduler = Scheduler::new();
+    let clock = ManualClock::new(at(10, 15, 30) + Duration::milliseconds(250));
+
+    assert_eq!(scheduler.next_deadline(clock.now()), at(10, 16, 0));
+    assert_eq!(scheduler.timeout(&clock), Some(StdDuration::from_millis(29_750)));
+}
+
+#[test]
+fn wakes_when_the_bar_moves_on() {
+    // 200 cells of 8 steps: a half step every 27 seconds
+    let mut scheduler = Scheduler::new();
+    scheduler.set_steps(1600);
+
+    assert_eq!(scheduler.next_deadline(at(10, 0, 0)), at(10, 0, 18));
+    assert_eq!(scheduler.next_deadline(at(10, 0, 18)), at(10, 0, 45));
+    // The minute comes first when the bar is coarse
+    scheduler.set_steps(80);
+    assert_eq!(scheduler.next_deadline(at(10, 0, 0)), at(10, 1, 0));
+}
+
+#[test]
+fn skips_frames_that_would_not_change() {
+    let mut scheduler = Scheduler::new();
+    scheduler.set_steps(1600);
+    let clock = ManualClock::new(at(10, 0, 0));
+
+    assert!(scheduler.frame_due(&clock));
+    assert!(!scheduler.frame_due(&clock));
+    clock.advance(Duration::seconds(17));
+    assert!(!scheduler.frame_due(&clock));
+    clock.advance(Duration::seconds(1));
+    assert!(scheduler.frame_due(&clock));
+
+    scheduler.invalidate();
+    assert!(scheduler.frame_due(&clock));
+    // A different bar size draws again too
+    scheduler.set_steps(800);
+    assert!(scheduler.frame_due(&clock));
+}
+
+#[test]
+fn timers_wake_it_once() {
+    let mut scheduler = Scheduler::new();
+    let clock = ManualClock::new(at(10, 0, 0));
+    scheduler.add_timer(at(10, 0, 5));
+    assert!(scheduler.frame_due(&clock));
+
+    assert_eq!(scheduler.timeout(&clock), Some(StdDuration::from_secs(5)));
+    clock.set(at(10, 0, 5));
+    assert!(scheduler.frame_due(&clock));
+    assert!(!scheduler.frame_due(&clock));
+    assert_eq!(scheduler.timeout(&clock), Some(StdDuration::from_secs(55)));
+}
+
+#[test]
+fn follows_the_speed_of_the_clock() {
+    let scheduler = Scheduler::new();
+
+    assert_eq!(scheduler.timeout(&FixedClock(at(10, 0, 30))), None);
+
+    let timeout = scheduler.timeout(&SimulatedClock::new(at(10, 0, 30), 60.0)).unwrap();
+    assert!(timeout <= StdDuration::from_millis(500));
+    assert!(timeout > StdDuration::from_millis(400));
+
+    let timeout = scheduler.timeout(&SimulatedClock::new(at(10, 0, 30), 100_000.0)).unwrap();
+    assert_eq!(timeout, MIN_INTERVAL);
+}
+
+#[test]
+fn simulated_clock_stops_at_the_end_of_time() {
+    let end = DateTime::<Utc>::MAX_UTC.with_timezone(&Local);
+    let clock = SimulatedClock::new(end - Duration::hours(1), 1e12);
+    std::thread::sleep(StdDuration::from_millis(5));
+    assert_eq!(clock.now(), end);
+}
@@ -0,0 +1,111 @@
+//! Frames rendered at a fixed moment through `t-meter snapshot`, compared
+//! with the files in `tests/snapshots`. After an intended change to the UI,
+//! rewrite them with `UPDATE_SNAPSHOTS=1 cargo test` and review the diff.
+
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+
+const AT: &str = "2026-10-16 14:35";
+
+const THEMES: [&str; 7] = ["default", "ocean", "forest", "sunset", "monochrome", "contrast", "daylight"];
+
+const STYLES: [&str; 9] =
+    ["gradient", "grainy", "analog", "smooth", "dots", "braille", "hours", "ascii", "custom"];
+
+/// Run `t-meter` with a fresh config in a temporary home
+fn t_meter(args: &[&str]) -> assert_cmd::assert::Assert {
+    let home = tempfile::tempdir().unwrap();
+    common::t_meter(home.path()).args(args).assert()
+}
+
+fn snapshot(args: &[&str]) -> String {
+    let mut all_args = vec!["snapshot", "--at", AT, "--width", "60", "--height", "22"];
+    all_args.extend(args);
+    let output = t_meter(&all_args).success().get_output().stdout.clone();
+    String::from_utf8(output).unwrap()
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(name);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("Missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it", path.display())
+    });
+    assert!(
+        actual == expected,
+        "Snapshot {} changed; run with UPDATE_SNAPSHOTS=1 if that was intended\n\n{}",
+        name,
+        actual
+    );
+}
+
+#[test]
+fn every_theme_in_both_modes() {
+    for theme in THEMES {
+        for mode in ["light", "dark"] {
+            let frame = snapshot(&["--format", "ansi", "--theme", theme, "--mode", mode]);
+            assert_snapshot(&format!("theme-{}-{}.ansi", theme, mode), &frame);
+        }
+    }
+}
+
+#[test]
+fn every_style() {
+    for style in STYLES {
+        let frame = snapshot(&["--format", "text", "--style", style]);
+        assert_snapshot(&format!("style-{}.txt", style), &frame);
+    }
+}
+
+#[test]
+fn compact_layout_in_a_small_split() {
+    let mut args = vec!["snapshot", "--at", "2026-10-16 09:00", "--width", "24", "--height", "8"];
+    args.extend(["--format", "text"]);
+    let output = t_meter(&args).success().get_output().stdout.clone();
+    let frame = String::from_utf8(output).unwrap();
+    assert_snapshot("compact-24x8.txt", &frame);
+}
+
+#[test]
+fn at_sets_the_time_shown() {
+    let frame = snapshot(&["--format", "text"]);
+    assert!(frame.contains("14:35"));
+    assert!(frame.contains("Elapsed:   14:35"));
+    assert!(frame.contains("Remaining: 09:25"));
+}
+
+#[test]
+fn at_rejects_invalid_times() {
+    t_meter(&["snapshot", "--at", "25:00"]).failure();
+    t_meter(&["snapshot", "--at", "yesterday"]).failure();
+}
+
+#[test]
+fn ansi_follows_the_palette() {
+    for (palette, extended) in
+        [("truecolor", Some("38;2;")), ("256", Some("38;5;")), ("16", None), ("none", None)]
+    {
+        let mut args = vec!["--colors", palette, "snapshot", "--at", AT];
+        args.extend(["--width", "60", "--height", "22", "--format", "ansi", "--theme", "ocean"]);
+        let output = t_meter(&args).success().get_output().stdout.clone();
+        let frame = String::from_utf8(output).unwrap();
+        assert_snapshot(&format!("palette-{}.ansi", palette), &frame);
+
+        for code in ["38;2;", "38;5;"] {
+            assert_eq!(frame.contains(code), extended == Some(code), "{} in {}", code, palette);
+        }
+        // Only resets and modifiers without a palette
+        let colored = frame.split("\x1b[").skip(1).any(|escape| {
+            let codes = escape.split('m').next().unwrap_or("");
+            codes.split(';').any(|code| code.parse::<u8>().is_ok_and(|code| code >= 30))
+        });
+        assert_eq!(colored, palette != "none", "{}", palette);
+    }
+}
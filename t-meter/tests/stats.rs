@@ -0,0 +1,64 @@
+//! Averages over the sleep log.
+
+use chrono::{DateTime, Duration, Local};
+use t_meter::history::History;
+use t_meter::stats::SleepStats;
+use t_meter::Schedule;
+
+mod common;
+use common::{at, date};
+
+/// A history planning 07:00 to 23:00 with these times marked
+fn history(woke_up: &[DateTime<Local>], went_to_bed: &[DateTime<Local>]) -> History {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    let mut history = History::new();
+    for &time in woke_up {
+        history.mark_wake(time, schedule);
+    }
+    for &time in went_to_bed {
+        history.mark_bed(time, schedule);
+    }
+    history
+}
+
+#[test]
+fn averages_drift_sleep_and_spread() {
+    let history = history(
+        &[at(10, 7, 0), at(11, 7, 30), at(12, 7, 30)],
+        &[at(10, 23, 30), at(12, 0, 30), at(12, 22, 30)],
+    );
+    let stats = SleepStats::over(history.days(), date(12), 7);
+
+    let wake = stats.wake.unwrap();
+    assert_eq!(wake.days, 3);
+    assert_eq!(wake.drift, Duration::minutes(20));
+    assert_eq!(wake.on_time, 1);
+    // 0, 30 and 30 minutes after the first
+    assert_eq!(wake.spread, Duration::seconds(849));
+
+    // Either side of midnight averages to half past eleven, not midday
+    let bed = stats.bed.unwrap();
+    assert_eq!(bed.drift, Duration::minutes(30));
+    assert_eq!(bed.on_time, 0);
+    assert_eq!(bed.spread, Duration::seconds(2939));
+
+    // 23:30 to 07:30 and 00:30 to 07:30
+    assert_eq!(stats.sleep, Some(Duration::minutes(450)));
+    assert_eq!(stats.nights, 2);
+}
+
+#[test]
+fn only_counts_days_in_the_span() {
+    let history = history(&[at(10, 6, 0), at(11, 8, 0)], &[at(10, 23, 0), at(11, 23, 0)]);
+
+    let last_day = SleepStats::over(history.days(), date(11), 1);
+    assert_eq!(last_day.wake.unwrap().drift, Duration::hours(1));
+    assert_eq!(last_day.wake.unwrap().spread, Duration::zero());
+    // The night before the 11th still counts towards it
+    assert_eq!(last_day.sleep, Some(Duration::hours(9)));
+
+    let later = SleepStats::over(history.days(), date(30), 7);
+    assert_eq!(later.wake, None);
+    assert_eq!(later.sleep, None);
+    assert_eq!(later.nights, 0);
+}
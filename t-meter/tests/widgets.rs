@@ -0,0 +1,282 @@
+//! Each widget of the library drawn on its own.
+
+use chrono::{Local, NaiveDate, TimeZone};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{StatefulWidget, Widget},
+};
+use t_meter::clock::FixedClock;
+use t_meter::history::History;
+use t_meter::quotes;
+use t_meter::style::{CustomGlyphs, Orientation};
+use t_meter::widgets::{
+    FloatingTime, HelpBox, HistoryChart, Legend, Marker, Markers, QuoteBlock,
+};
+use t_meter::{theme, DayBar, DayBarState, ProgressBarStyle, Schedule};
+
+/// The characters of each row, with trailing spaces trimmed
+fn rows(buffer: &Buffer) -> Vec<String> {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width)
+        .map(|cells| {
+            cells.iter().map(|cell| cell.symbol()).collect::<String>().trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Render `widget` into a `width` by `height` buffer
+fn draw(widget: impl Widget, width: u16, height: u16) -> Buffer {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, width, height));
+    widget.render(buffer.area, &mut buffer);
+    buffer
+}
+
+#[test]
+fn fills_up_to_the_time_with_markers() {
+    let theme = theme::get_default_theme();
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 24, 2));
+    let bar = DayBar::new(&theme.light)
+        .style(ProgressBarStyle::Ascii)
+        .at(12 * 3600)
+        .markers(Schedule::parse("06:00", "18:00").unwrap().markers());
+    Widget::render(bar, buffer.area, &mut buffer);
+
+    let expected = "[#####+#####|-----+----]";
+    assert_eq!(rows(&buffer), vec![expected, expected]);
+}
+
+#[test]
+fn vertical_bars_give_each_row_a_slice_of_the_day() {
+    let theme = theme::get_default_theme();
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 2, 8));
+    let bar = DayBar::new(&theme.light)
+        .style(ProgressBarStyle::Ascii)
+        .orientation(Orientation::Vertical)
+        .at(9 * 3600);
+    Widget::render(bar, buffer.area, &mut buffer);
+
+    assert_eq!(rows(&buffer), vec!["##", "##", "##", "==", "..", "..", "..", ".."]);
+}
+
+#[test]
+fn custom_glyphs_wider_than_a_cell_fall_back() {
+    let mut custom = CustomGlyphs::default();
+    custom.filled = "日".to_string();
+    custom.marker = "🌙".to_string();
+    custom.indicator = "*later".to_string();
+    assert_eq!(custom.too_wide(), [("filled", "="), ("marker", "|")]);
+
+    let glyphs = ProgressBarStyle::Custom.glyphs(Orientation::Horizontal, &custom);
+    assert_eq!(glyphs.filled, "=");
+    assert_eq!(glyphs.marker, "|");
+    assert_eq!(glyphs.indicator, "*");
+    assert_eq!(glyphs.empty, " ");
+
+    // The vertical layout's own glyphs stand in for its replacements
+    custom.vertical_indicator = Some("👉".to_string());
+    assert_eq!(custom.too_wide()[2], ("vertical_indicator", "━"));
+    let glyphs = ProgressBarStyle::Custom.glyphs(Orientation::Vertical, &custom);
+    assert_eq!(glyphs.indicator, "━");
+}
+
+#[test]
+fn state_supplies_the_time_and_records_the_indicator() {
+    let theme = theme::get_default_theme();
+    let moment = Local.with_ymd_and_hms(2026, 10, 16, 18, 0, 0).unwrap();
+    let mut state = DayBarState::new(FixedClock(moment));
+    assert_eq!(state.indicator(), None);
+
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 48, 1));
+    StatefulWidget::render(DayBar::new(&theme.dark), buffer.area, &mut buffer, &mut state);
+
+    assert_eq!(state.indicator(), Some(36));
+    assert_eq!(buffer[(36, 0)].symbol(), "┃");
+}
+
+#[test]
+fn floating_time_sits_over_the_indicator() {
+    let theme = theme::get_default_theme();
+    let buffer = draw(FloatingTime::new(&theme.light).at(12 * 3600), 24, 2);
+    assert_eq!(rows(&buffer), vec!["          12:00", "            ▼"]);
+}
+
+#[test]
+fn floating_time_stays_on_screen_at_the_ends() {
+    let theme = theme::get_default_theme();
+    let buffer = draw(FloatingTime::new(&theme.light).at(23 * 3600 + 59 * 60), 24, 2);
+    assert_eq!(rows(&buffer), vec!["                   23:59", "                       ▼"]);
+}
+
+#[test]
+fn markers_draw_ticks_times_and_labels() {
+    let theme = theme::get_default_theme();
+    let markers = [Marker::new(6 * 3600, "Up"), Marker::new(18 * 3600, "Bed")];
+    let buffer = draw(Markers::new(&theme.light, markers), 24, 3);
+    assert_eq!(
+        rows(&buffer),
+        vec!["      │           │", "    06:00       18:00", "     Up          Bed"]
+    );
+}
+
+#[test]
+fn markers_can_show_times_only_with_one_highlighted() {
+    let theme = theme::get_default_theme();
+    let markers = [Marker::new(6 * 3600, "Up").time("6:3").highlighted(true)];
+    let buffer = draw(Markers::new(&theme.light, markers).ticks(false).labels(false), 24, 3);
+
+    assert_eq!(rows(&buffer), vec!["     6:3", "", ""]);
+    assert_eq!(buffer[(5, 0)].fg, theme.light.highlight);
+    assert_eq!(buffer[(0, 0)].fg, theme.light.marker);
+}
+
+#[test]
+fn secondary_markers_move_beside_the_others() {
+    let theme = theme::get_default_theme();
+    let markers = [
+        Marker::new(6 * 3600, "Up"),
+        Marker::new(18 * 3600, "Bed"),
+        Marker::new(6 * 3600 + 15 * 60, "Late").secondary(true),
+        Marker::new(17 * 3600 + 45 * 60, "Early").secondary(true),
+    ];
+    let buffer = draw(Markers::new(&theme.light, markers), 48, 3);
+
+    assert_eq!(
+        rows(&buffer),
+        vec![
+            "            │                      ┊│",
+            "          06:00 06:15       17:45 18:00",
+            "           Up   Late        Early  Bed",
+        ]
+    );
+    assert_eq!(buffer[(16, 1)].fg, theme.light.marker_label);
+    assert_eq!(buffer[(10, 1)].fg, theme.light.marker);
+}
+
+#[test]
+fn quote_block_wraps_and_credits_the_author() {
+    let theme = theme::get_default_theme();
+    let quote =
+        quotes::get_quotes().into_iter().find(|q| q.text == "He who is brave is free.").unwrap();
+    let buffer = draw(QuoteBlock::new(&theme.light, quote), 16, 3);
+    assert_eq!(rows(&buffer), vec!["\"He who is brave", "    is free.\"", "    ~ Seneca"]);
+}
+
+#[test]
+fn legend_stacks_when_there_is_room() {
+    let theme = theme::get_default_theme();
+    let buffer = draw(Legend::new(&theme.light).at(14 * 3600 + 35 * 60), 24, 2);
+    assert_eq!(rows(&buffer), vec!["   ● Elapsed:   14:35", "   ○ Remaining: 09:25"]);
+}
+
+#[test]
+fn legend_fits_on_one_row() {
+    let theme = theme::get_default_theme();
+    let buffer = draw(Legend::new(&theme.light).at(14 * 3600 + 35 * 60), 48, 1);
+    assert_eq!(rows(&buffer), vec!["     ● Elapsed:   14:35   ○ Remaining: 09:25"]);
+}
+
+#[test]
+fn legend_leaves_out_what_does_not_fit() {
+    let theme = theme::get_default_theme();
+    let legend = || Legend::new(&theme.light).at(14 * 3600 + 35 * 60);
+    assert_eq!(rows(&draw(legend(), 24, 1)), vec!["   ● Elapsed:   14:35"]);
+    assert_eq!(rows(&draw(legend(), 10, 1)), vec!["  ● 14:35"]);
+    assert_eq!(rows(&draw(legend(), 10, 2)), vec!["  ● 14:35", "  ○ 09:25"]);
+}
+
+#[test]
+fn legend_stops_at_the_end_of_the_day() {
+    let theme = theme::get_default_theme();
+    let expected = vec!["   ● Elapsed:   24:00", "   ○ Remaining: 00:00"];
+    for seconds in [86_400, 100_000, u32::MAX] {
+        let buffer = draw(Legend::new(&theme.light).at(seconds), 24, 2);
+        assert_eq!(rows(&buffer), expected);
+    }
+}
+
+#[test]
+fn help_box_frames_the_keys() {
+    let theme = theme::get_default_theme();
+    let help =
+        HelpBox::new(&theme.light, "HELP").key("q", "Quit").gap().text("Press [Esc] to close");
+    let buffer = draw(help, 30, 7);
+
+    assert_eq!(
+        rows(&buffer),
+        vec![
+            "┌───────── HELP ─────────┐",
+            "│                        │",
+            "│  [q]     Quit          │",
+            "│                        │",
+            "│  Press [Esc] to close  │",
+            "│                        │",
+            "└────────────────────────┘",
+        ]
+        .into_iter()
+        .map(|row| format!("  {}", row))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(buffer[(5, 2)].fg, theme.light.highlight);
+    assert_eq!(buffer[(2, 0)].fg, theme.light.marker);
+}
+
+#[test]
+fn history_chart_draws_a_row_per_day() {
+    let theme = theme::get_default_theme();
+    let schedule = Schedule::parse("06:00", "18:00").unwrap();
+    let at = |day, hour| Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap();
+    let mut history = History::new();
+    history.mark_wake(at(16, 7), schedule);
+    history.start_focus(at(16, 9), schedule);
+    history.end_focus(at(16, 11));
+    history.mark_bed(at(16, 22), schedule);
+    history.mark_wake(at(17, 5), schedule);
+    history.record(at(17, 12), schedule);
+
+    let bar = DayBar::new(&theme.light).style(ProgressBarStyle::Ascii);
+    let last = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+    let buffer = draw(HistoryChart::new(bar, history.days(), last), 31, 3);
+
+    // Nothing on the 15th, the night of the 16th filled in and the 17th
+    // only seen until noon
+    assert_eq!(
+        rows(&buffer),
+        vec![
+            "Thu 15",
+            "Fri 16       +--##-------+---##",
+            "Sat 17 #####-+-----      +",
+        ]
+    );
+    // Focus is in the highlight color, sleep isn't
+    assert_eq!(buffer[(16, 1)].fg, theme.light.highlight);
+    assert_ne!(buffer[(29, 1)].fg, theme.light.highlight);
+    assert_eq!(buffer[(0, 0)].fg, theme.light.marker_label);
+}
+
+#[test]
+fn only_solid_glyph_sets_paint_behind_the_leading_edge() {
+    let theme = theme::get_default_theme();
+    let leading_edge = |style, orientation| {
+        let area = match orientation {
+            Orientation::Horizontal => Rect::new(0, 0, 24, 1),
+            _ => Rect::new(0, 0, 1, 24),
+        };
+        let mut buffer = Buffer::empty(area);
+        let bar = DayBar::new(&theme.light).style(style).orientation(orientation);
+        let bar = bar.at(9 * 3600 + 1800);
+        Widget::render(bar, area, &mut buffer);
+        buffer.content[9].clone()
+    };
+
+    for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+        let braille = leading_edge(ProgressBarStyle::Braille, orientation);
+        assert!(["⣇", "⡟"].contains(&braille.symbol()), "{}", braille.symbol());
+        assert_eq!(braille.bg, Color::Reset);
+    }
+    let smooth = leading_edge(ProgressBarStyle::Smooth, Orientation::Horizontal);
+    assert_eq!((smooth.symbol(), smooth.bg), ("▌", theme.light.progress_empty));
+}
@@ -0,0 +1,70 @@
+//! Winding down before bed: the time left, warmer colors, the quotes about
+//! rest and the colors of the night screen.
+
+use ratatui::style::Color;
+use t_meter::color;
+use t_meter::quotes;
+use t_meter::theme::{self, NightScheme};
+use t_meter::Schedule;
+
+fn hm(hour: u32, minute: u32) -> u32 {
+    hour * 3600 + minute * 60
+}
+
+#[test]
+fn counts_down_to_bed_time() {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    assert_eq!(schedule.until_bed(hm(22, 15)), hm(0, 45));
+    assert_eq!(schedule.until_bed(hm(23, 0)), 0);
+    assert_eq!(schedule.until_bed(hm(23, 30)), hm(23, 30));
+
+    // Bed time after midnight
+    let late = Schedule::parse("09:00", "01:30").unwrap();
+    assert_eq!(late.until_bed(hm(23, 0)), hm(2, 30));
+}
+
+#[test]
+fn night_runs_from_bed_time_to_wake_up() {
+    let schedule = Schedule::parse("07:00", "23:00").unwrap();
+    assert!(!schedule.is_night(hm(22, 59)));
+    assert!(schedule.is_night(hm(23, 0)));
+    assert!(schedule.is_night(hm(3, 0)));
+    assert!(!schedule.is_night(hm(7, 0)));
+
+    let late = Schedule::parse("09:00", "01:30").unwrap();
+    assert!(!late.is_night(hm(23, 0)));
+    assert!(late.is_night(hm(2, 0)));
+    assert!(!late.is_night(hm(12, 0)));
+}
+
+#[test]
+fn warms_colors_by_cutting_blue() {
+    let white = Color::Rgb(255, 255, 255);
+    assert_eq!(color::warm(white, 0.0), white);
+    assert_eq!(color::warm(white, 1.0), Color::Rgb(255, 204, 102));
+    assert_eq!(color::warm(white, 0.5), Color::Rgb(255, 230, 179));
+    assert_eq!(color::warm(Color::Reset, 1.0), Color::Reset);
+
+    let scheme = theme::get_ocean_theme().dark;
+    let warm = scheme.warmer(1.0);
+    assert_eq!(warm.foreground, color::warm(scheme.foreground, 1.0));
+    assert_eq!(warm.background, scheme.background.map(|c| color::warm(c, 1.0)));
+    assert_eq!(warm.gradient.len(), scheme.gradient.len());
+    assert_eq!(warm.gradient_mode, scheme.gradient_mode);
+}
+
+#[test]
+fn has_its_own_quotes() {
+    let wind_down = quotes::get_wind_down_quotes();
+    assert!(wind_down.len() >= 2);
+    assert_ne!(quotes::wind_down_for_hour(22), quotes::wind_down_for_hour(23));
+    assert!(!quotes::get_quotes().contains(&quotes::wind_down_for_hour(22)));
+}
+
+#[test]
+fn reads_night_colors_from_toml() {
+    let night: NightScheme = toml::from_str("foreground = \"#505050\"").unwrap();
+    assert_eq!(night.foreground, Color::Rgb(0x50, 0x50, 0x50));
+    assert_eq!(night.accent, NightScheme::default().accent);
+    assert_eq!(night.background, NightScheme::default().background);
+}